@@ -0,0 +1,31 @@
+use clocktui::app::App;
+use clocktui::config::AppConfig;
+use clocktui::font::Font;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use figlet_rs::FIGfont;
+use tui::backend::TestBackend;
+use tui::Terminal;
+
+/// Drawing a token with the font parsed afresh each time, as every frame used to, against drawing
+/// it with the font loaded once.
+fn figure(c: &mut Criterion) {
+    let mut group = c.benchmark_group("figure");
+    group.bench_function("reloaded font", |b| {
+        b.iter(|| FIGfont::standand().unwrap().convert(black_box("12")).map(|figure| figure.to_string()))
+    });
+    let font = Font::standard();
+    group.bench_function("cached font", |b| {
+        b.iter(|| font.convert(black_box("12")).map(|figure| figure.to_string()))
+    });
+    group.finish();
+}
+
+/// A whole frame of the default clock face.
+fn render(c: &mut Criterion) {
+    let mut app = App::new(AppConfig::default()).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    c.bench_function("render", |b| b.iter(|| terminal.draw(|frame| app.render(frame)).map(|_| ())));
+}
+
+criterion_group!(benches, figure, render);
+criterion_main!(benches);
@@ -1,78 +1,492 @@
+use std::collections::VecDeque;
 use std::error;
-use std::fmt::Display;
-use std::time::Duration;
+use std::fmt::{Display, Write as _};
+use std::fs;
+use std::io::{self, Write};
+use std::mem;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
 
-use figlet_rs::FIGfont;
-use tui::backend::Backend;
-use tui::layout::{Layout, Direction, Constraint, Alignment};
-use tui::terminal::Frame;
-use tui::widgets::{Block, Borders, Paragraph, BorderType, Clear};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use image::ImageFormat;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tui::backend::{Backend, TestBackend};
+use tui::layout::{Layout, Direction, Constraint, Alignment, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::symbols::Marker;
+use tui::terminal::{Frame, Terminal};
+use tui::widgets::canvas::{Canvas, Line, Points};
+use tui::text::{Span, Spans};
+use tui::widgets::{Axis, BarChart, Block, Borders, Chart, Dataset, GraphType, Paragraph, BorderType, Clear, Gauge, Row, Sparkline, Table, Tabs};
 
 use chrono::prelude::*;
+use chrono_tz::Tz;
 
-use crate::event::EventHandler;
+#[cfg(feature = "audio")]
+use crate::audio::AudioPlayer;
+#[cfg(feature = "clipboard")]
+use crate::clipboard::TimeClipboard;
+#[cfg(feature = "mem-limit")]
+use crate::memory;
+#[cfg(feature = "network")]
+use crate::news::NewsTicker;
+#[cfg(feature = "network")]
+use crate::ntp::NtpMonitor;
+#[cfg(feature = "network")]
+use crate::weather::Weather;
+use crate::color::{gradient_char, parse_color, ColorDepth, ColorDepthFilter, ColorMode};
+use crate::confetti::Confetti;
+use crate::fireworks::{self, Fireworks};
+use crate::config::{AppConfig, ConfigFile, GradientDirection, HorizontalAlign, RingSpan, TimingSpec, VerticalAlign};
+use crate::easing::{cubic_bezier, EasingFn};
+use crate::event::{AnimationTrigger, NoopAnimationTrigger, BLINK_TICK_RATE, RENDER_TICK_RATE};
+use crate::export::{self, ExportBlock, FACE_HEIGHT};
+use crate::font::{self, Font};
+use crate::holiday::HolidayColorMap;
+use crate::style::{StyleFile, StyleSheet};
+use crate::lock::PinLock;
+use crate::moon;
+use crate::morse;
+use crate::precision::TickPrecision;
+use crate::preset::{self, Preset};
+use crate::shape::{self, Circle, Transparent};
+use crate::slideshow::Slideshow;
+use crate::stopwatch::Stopwatch;
+use crate::sun;
+use crate::voronoi::Voronoi;
+use crate::watch::Watch;
+use crate::transition::{self, GravityTransition, Particle, SlotMachineTransition, TypewriterTransition, WipeDirection};
+
+/// How long the border stays lit after a beat.
+#[cfg(feature = "beat-sync")]
+const BEAT_PULSE: Duration = Duration::from_millis(150);
+
+/// Format string of the time unless configured otherwise.
+const DEFAULT_FORMAT: &str = "%X";
+
+/// How often the `--config` file is checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long the colours stay inverted at noon or midnight.
+const INVERT_DURATION: Duration = Duration::from_secs(5);
+
+/// How long the clock flashes at the top of each hour.
+const FLASH_DURATION: Duration = Duration::from_millis(500);
+
+/// How long the `--memory-limit` notice stays on the bottom row of the screen.
+#[cfg(feature = "mem-limit")]
+const MEMORY_NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+/// Width of the `--show-timezone-offset` suffix, including the gap before it.
+const TIMEZONE_OFFSET_WIDTH: u16 = 7;
+
+/// Number of values shown by `--sparkline-seconds`.
+const SPARKLINE_LENGTH: usize = 60;
+
+/// Width and height of the terminal drawn to by `--benchmark`, in terminal cells.
+const BENCHMARK_SIZE: (u16, u16) = (120, 40);
+/// Number of frames drawn by `--benchmark`.
+pub const BENCHMARK_FRAMES: u32 = 1000;
+/// Render time budgeted for each second when `--frame-rate-limit` isn't given, in milliseconds,
+/// about 5% of one core.
+const FRAME_BUDGET: f64 = 50.0;
+/// Render time each digit block costs per frame, in milliseconds, measured with `--benchmark`.
+const BLOCK_FRAME_COST: f64 = 0.2;
+/// Fewest frames per second picked by default, below which transitions visibly stutter.
+const MIN_DEFAULT_FPS: u64 = 10;
+/// Height of the clock face drawn `--compact`, just the lines of the figures, in terminal cells.
+const COMPACT_FACE_HEIGHT: u16 = 6;
+/// Width of each character of a digit block drawn `--compact`, enough for the widest digit of the
+/// standard font, in terminal cells.
+const COMPACT_DIGIT_WIDTH: u16 = 10;
+/// Height of the `--date` row above the clock face, in terminal cells.
+const DATE_HEIGHT: u16 = 3;
+/// Height of the `--progress-bar`, in terminal cells.
+const PROGRESS_BAR_HEIGHT: u16 = 3;
+/// Height of the `--sparkline-seconds` chart, in terminal cells.
+const SPARKLINE_HEIGHT: u16 = 3;
+
+/// Hours from now of each row of the `--table-mode` table.
+const TABLE_OFFSETS: [i64; 6] = [0, 1, 2, 4, 8, 24];
+
+/// Height of the `--barchart-seconds` chart, in terminal cells.
+const BARCHART_HEIGHT: u16 = 6;
+
+/// Height of the `--chart-history` chart, in terminal cells.
+const CHART_HEIGHT: u16 = 8;
+
+/// Width of the stopwatch lap panel, in terminal cells.
+const LAP_PANEL_WIDTH: u16 = 28;
+
+/// Speed of the innermost `--hypno` ring, in revolutions per minute.
+const HYPNO_INNER_RPM: f64 = 10.0;
+
+/// Speed of the outermost `--hypno` ring, in revolutions per minute.
+const HYPNO_OUTER_RPM: f64 = 1.0;
+
+/// Render ticks left below which `--particle-trail` particles are dimmed.
+const PARTICLE_DIM_LIFETIME: u16 = 15;
+
+/// Fastest transition used by `--adaptive-timing`, for digits changing every second.
+const ADAPTIVE_TIMING_MIN: u128 = 150;
+/// Slowest transition used by `--adaptive-timing`, for digits changing hourly or less.
+const ADAPTIVE_TIMING_MAX: u128 = 800;
+
+/// Round trip time filling the `--network-time-display` gauge.
+#[cfg(feature = "network")]
+const NTP_GAUGE_MAX: Duration = Duration::from_millis(200);
 
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
 
-#[derive(Debug, Clone, Default)]
-struct TokenBlock {
+/// A run of characters of the time drawn in its own box, transitioning whenever they change.
+#[derive(Debug, Clone)]
+pub struct TokenBlock {
     pub is_constant: bool,
     pub transition_progress: u128,
     pub transition_timing: u128,
+    /// How long after the time changes the transition starts, in milliseconds, for `--stagger`.
+    pub stagger_delay: u128,
+    /// Time waited so far towards `stagger_delay`, in milliseconds.
+    pub stagger_elapsed: u128,
     pub size: usize,
     pub curr_token: String,
     pub new_token: String,
+    /// When `new_token` last took on a different value.
+    pub last_changed: Instant,
+    /// Direction of the current transition, when picked per block.
+    pub direction: WipeDirection,
+    /// Spring position of the transition, from 0.0 (old token) to 1.0 (new token).
+    pub position: f32,
+    /// Spring velocity of the transition, in positions per second.
+    pub velocity: f32,
+    /// Stiffness of the spring pulling the transition towards the new token.
+    pub spring_constant: f32,
+    /// Fall of the new token, when transitions use gravity.
+    pub gravity: GravityTransition,
+    /// Reel spinning towards the new token, when transitions use a slot machine.
+    pub slot_machine: Option<SlotMachineTransition>,
+    /// Typing out of the new token, when words use a typewriter transition.
+    pub typewriter: Option<TypewriterTransition>,
+    /// Particles left behind by old tokens, when transitions leave a particle trail.
+    pub particles: VecDeque<Particle>,
+    /// Colours of the block, from `--fg` and `--bg`.
+    pub style: Style,
+    /// Colours of the new token as it wipes in, from `--transition-fg`.
+    pub transition_style: Style,
+    /// Whether the block is a separator blinked by `--blink-separator`.
+    pub blink: bool,
+    /// Whether a blinking block is showing, rather than blanked out.
+    pub blink_on: bool,
+}
+
+impl Default for TokenBlock {
+    fn default() -> Self {
+        Self {
+            is_constant: false,
+            transition_progress: 0,
+            transition_timing: 0,
+            stagger_delay: 0,
+            stagger_elapsed: 0,
+            size: 0,
+            curr_token: String::new(),
+            new_token: String::new(),
+            last_changed: Instant::now(),
+            direction: WipeDirection::TopDown,
+            position: 0.0,
+            velocity: 0.0,
+            spring_constant: 0.0,
+            gravity: GravityTransition::default(),
+            slot_machine: None,
+            typewriter: None,
+            particles: VecDeque::new(),
+            style: Style::default(),
+            transition_style: Style::default(),
+            blink: false,
+            blink_on: true,
+        }
+    }
 }
 
+impl TokenBlock {
+    /// Width of the block on screen, in terminal cells.
+    pub fn width(&self) -> u16 {
+        let width = self.size * match self.is_constant {
+            true => 8,
+            false => 15
+        };
+        width as u16
+    }
+
+    /// Token to draw for the block, including any slot machine reel or typing in progress.
+    pub fn displayed_token(&self) -> &str {
+        match (&self.slot_machine, &self.typewriter) {
+            (Some(reel), _) => &reel.token,
+            (_, Some(typewriter)) => &typewriter.text,
+            _ => &self.curr_token
+        }
+    }
+}
+
+/// A single token of the format string, such as `%H`, and the blocks it's drawn as.
 #[derive(Debug, Clone)]
-struct Token {
+pub struct Token {
     pub format_string: String,
     pub blocks: Vec<TokenBlock>
 }
 
+/// What the displayed time counts.
+#[derive(Debug, Clone, Copy)]
+enum TimeSource {
+    /// The time of day.
+    Clock,
+    /// Time counted by the stopwatch.
+    Stopwatch(Stopwatch),
+    /// Time left until the countdown ends.
+    Countdown(Instant),
+}
+
+/// The time formatted into blocks, animating each block between its old and new tokens.
 #[derive(Debug, Clone)]
-struct AnimatedTime {
+pub struct AnimatedTime {
     pub format_tokens: Vec<Token>,
+    /// The time as formatted before it last changed.
+    pub prev_formatted: String,
+    /// When any block last changed.
+    pub last_changed: Instant,
+    /// What the displayed time counts.
+    source: TimeSource,
+    /// Picks a random direction for each transition, when set.
+    direction_rng: Option<StdRng>,
+    /// Scales each block's timing by how often it changes, when set.
+    adaptive_timing: bool,
+    /// Stiffness and damping of the spring driving transitions, if transitions use spring physics.
+    spring: Option<(f32, f32)>,
+    /// Strength and restitution of gravity driving transitions, if transitions use gravity.
+    gravity: Option<(f32, f32)>,
+    /// Spins changing digits like a slot machine reel, when set.
+    slot_machine: bool,
+    /// Types out multi-character blocks, when set.
+    typewriter: bool,
+    /// Scatters particles where old tokens were, when set.
+    particle_rng: Option<StdRng>,
+    /// Font the particles are scattered from the figures of, if not the standard font.
+    font: Option<Font>,
+    /// Timezone the clock is shown in, if not the local one.
+    timezone: Option<Tz>,
+    /// Shows new tokens straight away instead of transitioning to them, when set.
+    no_animation: bool,
+    /// Timings of the tokens of particular units of time, overriding `timing`.
+    timing_specs: Vec<TimingSpec>,
+    /// Easing applied to timed transitions.
+    easing: EasingFn,
+    /// Colours of every block.
+    style: Style,
+    /// Colours of new tokens as they wipe in.
+    transition_style: Style,
+    /// Delay between the start of each digit's transition and the next one's, in milliseconds.
+    stagger: u128,
+    /// Blinks separators such as `:`, when set.
+    blink_separator: bool,
+    /// Time since separators last blinked, in milliseconds.
+    blink_elapsed: u128,
+    /// Where each block was last drawn on screen, in the order of the blocks.
+    block_rects: Vec<Rect>,
+    /// Whole days shown before the time of the stopwatch or countdown, which `%H` wraps past.
+    days: u64,
     timing: u128
 }
 
+impl Default for AnimatedTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AnimatedTime {
+    /// Constructs the local time in the default `%X` format.
     pub fn new() -> Self {        
-        Self { format_tokens: Vec::new(), timing: 250 }.set_format("%X")
+        Self { format_tokens: Vec::new(), prev_formatted: String::new(), last_changed: Instant::now(), source: TimeSource::Clock, direction_rng: None, adaptive_timing: false, spring: None, gravity: None, slot_machine: false, typewriter: false, particle_rng: None, font: None, timezone: None, no_animation: false, timing_specs: Vec::new(), easing: EasingFn::Linear, style: Style::default(), transition_style: Style::default(), stagger: 0, blink_separator: false, blink_elapsed: 0, block_rects: Vec::new(), days: 0, timing: 250 }.set_format(DEFAULT_FORMAT)
+    }
+
+    pub fn set_random_direction(mut self, is_random: bool) -> Self {
+        self.direction_rng = is_random.then(StdRng::from_entropy);
+        self
     }
 
     pub fn set_timing(mut self, timing: u128) -> Self {
+        self.timing = timing;
+        self.apply_timing();
+        self
+    }
+
+    /// Sets the transition timing of the tokens matching each of `specs`, leaving other tokens
+    /// at the overall timing.
+    pub fn set_timing_per_token(mut self, specs: &[TimingSpec]) -> Self {
+        self.timing_specs = specs.to_vec();
+        self.apply_timing();
+        self
+    }
+
+    /// Eases timed transitions along `easing`.
+    pub fn set_easing(mut self, easing: EasingFn) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Colours every block with `style`, and new tokens with `transition_style` as they wipe in.
+    pub fn set_style(mut self, style: Style, transition_style: Style) -> Self {
+        self.style = style;
+        self.transition_style = transition_style;
+        for block in self.format_tokens.iter_mut().flat_map(|token| &mut token.blocks) {
+            block.style = style;
+            block.transition_style = transition_style;
+        }
+        self
+    }
+
+    /// Starts the transition of each digit `stagger` milliseconds after the one before it.
+    pub fn set_stagger(mut self, stagger: u128) -> Self {
+        self.stagger = stagger;
+        self.apply_timing();
+        self
+    }
+
+    /// Blinks separators such as `:` on and off every half second.
+    pub fn set_blink_separator(mut self, is_blink_separator: bool) -> Self {
+        self.blink_separator = is_blink_separator;
+        self.apply_blink();
+        self
+    }
+
+    /// Marks constant blocks that aren't letters or digits as blinking separators.
+    fn apply_blink(&mut self) {
+        for block in self.format_tokens.iter_mut().flat_map(|token| &mut token.blocks) {
+            block.blink = self.blink_separator && block.is_constant && !block.curr_token.chars().any(char::is_alphanumeric);
+            block.blink_on = true;
+        }
+    }
+
+    /// Drives transitions by a damped spring instead of a fixed duration.
+    pub fn set_spring(mut self, spring: Option<(f32, f32)>) -> Self {
+        self.spring = spring;
+        self.apply_timing();
+        self
+    }
+
+    /// Drives transitions by dropping the new token in under gravity.
+    pub fn set_gravity(mut self, gravity: Option<(f32, f32)>) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Spins changing digits through the digits in between like a slot machine.
+    pub fn set_slot_machine(mut self, is_slot_machine: bool) -> Self {
+        self.slot_machine = is_slot_machine;
+        self
+    }
+
+    /// Types out blocks of more than one character, such as weekday names.
+    pub fn set_typewriter(mut self, is_typewriter: bool) -> Self {
+        self.typewriter = is_typewriter;
+        self
+    }
+
+    /// Shows new tokens as soon as they change, without any transition.
+    pub fn set_no_animation(mut self, is_no_animation: bool) -> Self {
+        self.no_animation = is_no_animation;
+        self.apply_timing();
+        self
+    }
+
+    /// Shows the clock in `timezone` instead of the local timezone.
+    pub fn set_timezone(mut self, timezone: Option<Tz>) -> Self {
+        self.timezone = timezone;
+        // Block sizes depend on the timezone, as with `%Z`
+        let format = self.format_string();
+        self.set_format(&format)
+    }
+
+    /// Leaves a trail of decaying particles where each old token was.
+    pub fn set_particle_trail(mut self, is_particle_trail: bool) -> Self {
+        self.particle_rng = is_particle_trail.then(StdRng::from_entropy);
+        self
+    }
+
+    /// Scatters particles from figures drawn in `font`.
+    pub fn set_font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Whether transitions drop the new token in under gravity.
+    pub fn is_gravity(&self) -> bool {
+        self.gravity.is_some()
+    }
+
+    pub fn set_adaptive_timing(mut self, is_adaptive: bool) -> Self {
+        self.adaptive_timing = is_adaptive;
+        self.apply_timing();
+        self
+    }
+
+    /// Sets the transition timing and spring stiffness of every block.
+    ///
+    /// With adaptive timing, blocks that change every second get the fastest
+    /// transition and blocks that change hourly or less get the slowest, on a
+    /// log scale in between. Blocks of a unit with a timing spec take its timing
+    /// instead, and constant blocks never transition.
+    fn apply_timing(&mut self) {
+        let reference = Local.ymd(2222, 2, 2).and_hms(1, 0, 0);
         for token in &mut self.format_tokens {
+            let periods = [1, 10, 60, 600, 3600, 36000, 86400].map(|period| {
+                let changed = format_token(&(reference + chrono::Duration::seconds(period)), &token.format_string);
+                (period, changed)
+            });
+            let reference = format_token(&reference, &token.format_string);
+            let mut offset = 0;
             for block in &mut token.blocks {
-                block.transition_timing = self.timing;
+                let block_chars = |time_string: &str| time_string.chars().skip(offset).take(block.size).collect::<String>();
+                let period = periods.iter()
+                    .find(|(_, changed)| block_chars(changed) != block_chars(&reference))
+                    .map(|(period, _)| *period);
+                // The last matching spec wins, as when a unit is given twice
+                let spec = period.and_then(|period| self.timing_specs.iter().rev().find(|spec| spec.matches(period)));
+                block.spring_constant = self.spring.map_or(0.0, |(spring_constant, _)| spring_constant);
+                block.transition_timing = match (period, spec) {
+                    _ if self.no_animation || block.is_constant => 0,
+                    (_, Some(spec)) => spec.timing,
+                    (Some(period), None) if self.adaptive_timing => {
+                        let scale = ((period as f64).ln() / 3600f64.ln()).min(1.0);
+                        ADAPTIVE_TIMING_MIN + ((ADAPTIVE_TIMING_MAX - ADAPTIVE_TIMING_MIN) as f64 * scale) as u128
+                    }
+                    _ => self.timing
+                };
+                offset += block.size;
             }
         }
-        
-        self
     }
 
     pub fn set_format(mut self, format_string: &str) -> Self {
-        let max_dt = Local.ymd(3000, 11, 11).and_hms_nano(12, 11, 11, 111111111);
-        let min_dt = Local.ymd(2222, 2, 2).and_hms_nano(1, 0, 0, 0);
-        // let max_dt = Local::now();
-        // let min_dt = Local::now();
-
         self.format_tokens.clear();
 
         let mut token = String::new();
         for ch in format_string.to_string().chars() {
             token.push(ch);
-            if !token.starts_with('%') || token.len() > 2 || (token.len() == 2 && !"-_0".contains(ch)) {
-                let max_dt = max_dt.format(&token).to_string();
-                let min_dt = min_dt.format(&token).to_string();
+            // Digits after the `%` are the width of fractional seconds, as in `%3f`
+            if !token.starts_with('%') || token.len() > 2 || (token.len() == 2 && !"-_0123456789".contains(ch)) {
+                let (max_dt, min_dt) = match self.timezone {
+                    Some(tz) => format_extremes(&tz, &token),
+                    None => format_extremes(&Local, &token)
+                };
 
                 let mut blocks: Vec<TokenBlock> = Vec::new();
                 if min_dt.len() != max_dt.len() {
-                    blocks.push(TokenBlock { is_constant: max_dt == min_dt, transition_progress: 0, transition_timing: self.timing, size: min_dt.len().max(max_dt.len()), ..TokenBlock::default() });
+                    blocks.push(TokenBlock { is_constant: max_dt == min_dt, transition_progress: 0, transition_timing: self.timing, size: min_dt.len().max(max_dt.len()), style: self.style, transition_style: self.transition_style, ..TokenBlock::default() });
                 } else {
                     for (min_ch, max_ch) in min_dt.chars().zip(max_dt.chars()) {
-                        blocks.push(TokenBlock{ is_constant: min_ch == max_ch, transition_progress: 0, transition_timing: self.timing, size: 1, ..TokenBlock::default()});
+                        blocks.push(TokenBlock{ is_constant: min_ch == max_ch, transition_progress: 0, transition_timing: self.timing, size: 1, style: self.style, transition_style: self.transition_style, ..TokenBlock::default()});
                     }
                 }
                 self.format_tokens.push(Token {format_string: token, blocks});
@@ -80,40 +494,293 @@ impl AnimatedTime {
             }
         }
 
+        // A face with tokens that came out empty would be missing pieces, so show the default instead
+        if format_string != DEFAULT_FORMAT && (self.format_tokens.is_empty() || self.format_tokens.iter().any(|token| token.blocks.is_empty())) {
+            return self.set_format(DEFAULT_FORMAT);
+        }
+
+        self.apply_timing();
         self.tick_logic();
         for token in &mut self.format_tokens {
             for block in &mut token.blocks {
                 block.curr_token = block.new_token.clone();
             }
         }
+        self.apply_blink();
 
         self
     }
 
+    /// The time as currently drawn, lagging behind [`formatted`] while transitions are in progress.
+    ///
+    /// [`formatted`]: AnimatedTime::formatted
+    pub fn displayed(&self) -> String {
+        self.format_tokens.iter()
+            .flat_map(|token| &token.blocks)
+            .map(|block| block.displayed_token())
+            .collect()
+    }
+
+    /// The time as currently formatted, ignoring any transitions in progress.
+    pub fn formatted(&self) -> String {
+        self.format_tokens.iter()
+            .flat_map(|token| &token.blocks)
+            .map(|block| block.new_token.as_str())
+            .collect()
+    }
+
+    /// The format string the time is displayed with.
+    pub fn format_string(&self) -> String {
+        self.format_tokens.iter().map(|token| token.format_string.as_str()).collect()
+    }
+
+    /// Shows the time elapsed since now instead of the time of day.
+    pub fn set_stopwatch(mut self, is_stopwatch: bool) -> Self {
+        if is_stopwatch {
+            self.source = TimeSource::Stopwatch(Stopwatch::start());
+        }
+        self
+    }
+
+    /// The stopwatch, if counting one.
+    pub fn stopwatch(&self) -> Option<&Stopwatch> {
+        match &self.source {
+            TimeSource::Stopwatch(stopwatch) => Some(stopwatch),
+            _ => None
+        }
+    }
+
+    /// The stopwatch, if counting one, for pausing and resetting it.
+    pub fn stopwatch_mut(&mut self) -> Option<&mut Stopwatch> {
+        match &mut self.source {
+            TimeSource::Stopwatch(stopwatch) => Some(stopwatch),
+            _ => None
+        }
+    }
+
+    /// Shows the time left until `duration` from now instead of the time of day.
+    pub fn set_countdown(mut self, duration: Option<Duration>) -> Self {
+        if let Some(duration) = duration {
+            self.source = TimeSource::Countdown(Instant::now() + duration);
+        }
+        self
+    }
+
+    /// Time left on the countdown, if counting down.
+    pub fn countdown_remaining(&self) -> Option<Duration> {
+        match self.source {
+            TimeSource::Countdown(end) => Some(end.saturating_duration_since(Instant::now())),
+            _ => None
+        }
+    }
+
     pub fn tick_logic(&mut self) {
-        let dt = Local::now(); // Add timezone stuff
-        for token in &mut self.format_tokens {
-            let time_string = dt.format(&token.format_string).to_string();
+        // Durations count from a midnight with no DST transition nearby
+        let midnight = || Local.ymd(2000, 1, 1).and_hms(0, 0, 0);
+        let counted = match self.source {
+            TimeSource::Clock => None,
+            TimeSource::Stopwatch(stopwatch) => Some(stopwatch.elapsed()),
+            TimeSource::Countdown(end) => Some(end.saturating_duration_since(Instant::now()))
+        };
+        if let Some(days) = counted.map(|counted| counted.as_secs() / 86400).filter(|&days| days != self.days) {
+            self.set_days(days);
+        }
+        let time_strings = match (self.source, self.timezone) {
+            (TimeSource::Clock, Some(tz)) => self.format_tokens_at(&Utc::now().with_timezone(&tz)),
+            (TimeSource::Clock, None) => self.format_tokens_at(&Local::now()),
+            (TimeSource::Stopwatch(stopwatch), _) => self.format_tokens_at(&(midnight()
+                + chrono::Duration::from_std(stopwatch.elapsed()).unwrap_or_else(|_| chrono::Duration::zero()))),
+            (TimeSource::Countdown(end), _) => self.format_tokens_at(&(midnight()
+                + chrono::Duration::from_std(end.saturating_duration_since(Instant::now())).unwrap_or_else(|_| chrono::Duration::zero()))),
+        };
+        self.show(time_strings);
+    }
+
+    /// Starts a transition on every digit, advancing each by one, as if the time had changed.
+    pub fn advance_digits(&mut self) {
+        let advance = |c: char| c.to_digit(10).and_then(|digit| char::from_digit((digit + 1) % 10, 10)).unwrap_or(c);
+        let time_strings = self.format_tokens.iter()
+            .map(|token| token.blocks.iter().flat_map(|block| block.new_token.chars()).map(advance).collect())
+            .collect();
+        self.show(time_strings);
+    }
+
+    /// Whether any block is still transitioning to its new token.
+    pub fn is_transitioning(&self) -> bool {
+        self.format_tokens.iter().flat_map(|token| &token.blocks)
+            .any(|block| block.new_token != block.curr_token || self.transition_progress(block).is_some())
+    }
+
+    /// Starts transitions to `time_strings`, one per format token, on the blocks they change.
+    fn show(&mut self, time_strings: Vec<String>) {
+        let now = Instant::now();
+        let formatted = self.formatted();
+        let font = self.particle_rng.is_some().then(|| self.font.clone().unwrap_or_else(Font::standard));
+        // Each changed digit waits one more stagger delay than the changed digit before it
+        let mut changed = 0;
+        for (token, time_string) in self.format_tokens.iter_mut().zip(time_strings) {
             let mut time_chars = time_string.chars();
             for block in &mut token.blocks {
-                block.new_token = (&mut time_chars).take(block.size).collect();
+                let new_token: String = (&mut time_chars).take(block.size).collect();
+                if new_token != block.new_token {
+                    if let (Some(rng), Some(figure)) = (&mut self.particle_rng, font.as_ref().and_then(|font| font.convert(block.displayed_token()))) {
+                        transition::scatter_particles(&figure.to_string(), rng, &mut block.particles);
+                    }
+                    block.new_token = new_token;
+                    block.stagger_elapsed = 0;
+                    if !block.is_constant {
+                        block.stagger_delay = self.stagger * changed;
+                        changed += 1;
+                    }
+                    if self.no_animation {
+                        block.curr_token = block.new_token.clone();
+                    }
+                    block.last_changed = now;
+                    self.last_changed = now;
+                    if let Some(rng) = &mut self.direction_rng {
+                        block.direction = WipeDirection::from_index(rng.gen_range(0..4));
+                    }
+                }
             }
         }
+        if self.last_changed == now {
+            self.prev_formatted = formatted;
+        }
+    }
+
+    /// Shows `days` whole days before the time of the stopwatch or countdown, or none if zero.
+    fn set_days(&mut self, days: u64) {
+        let prefix = |days| match days {
+            0 => String::new(),
+            days => format!("{}d ", days)
+        };
+        let format = self.format_string();
+        let format = format!("{}{}", prefix(days), format.strip_prefix(&prefix(self.days)).unwrap_or(&format));
+        self.days = days;
+        *self = mem::take(self).set_format(&format);
+    }
+
+    /// Formats `dt` with each token of the format string.
+    fn format_tokens_at<Tz: TimeZone>(&self, dt: &DateTime<Tz>) -> Vec<String>
+    where
+        Tz::Offset: Display,
+    {
+        self.format_tokens.iter().map(|token| format_token(dt, &token.format_string)).collect()
+    }
+
+    /// Drops the particles left over from transitions, freeing their memory.
+    pub fn clear_particles(&mut self) {
+        for block in self.format_tokens.iter_mut().flat_map(|tokens| &mut tokens.blocks) {
+            block.particles = VecDeque::new();
+        }
+    }
+
+    /// Index of the block drawn at the screen position (`x`, `y`) in the last frame, if any.
+    pub fn block_at_screen_pos(&self, x: u16, y: u16) -> Option<usize> {
+        self.block_rects.iter().position(|rect| rect.x <= x && x < rect.right() && rect.y <= y && y < rect.bottom())
+    }
+
+    /// Whether the block was left unchanged by the most recent update.
+    pub fn is_unchanged(&self, block: &TokenBlock) -> bool {
+        block.last_changed < self.last_changed
+    }
+
+    /// How far through its transition a block is, from 0.0 to 1.0, if transitioning.
+    ///
+    /// Timed transitions are eased; spring and gravity transitions move as their physics dictate.
+    pub fn transition_progress(&self, block: &TokenBlock) -> Option<f64> {
+        if self.gravity.is_some() {
+            return (block.gravity.y > 0.0).then_some(block.gravity.y as f64);
+        }
+        match self.spring {
+            Some(_) => (block.position > 0.0).then(|| block.position.clamp(0.0, 1.0) as f64),
+            None => (block.transition_progress > 0)
+                .then(|| self.easing.apply(block.transition_progress as f64 / block.transition_timing.max(1) as f64)),
+        }
     }
 
     pub fn tick_render(&mut self, duration: Duration) -> bool {
+        if self.blink_separator {
+            self.blink_elapsed += duration.as_millis();
+            if self.blink_elapsed >= BLINK_TICK_RATE as u128 {
+                self.blink_elapsed %= BLINK_TICK_RATE as u128;
+                for block in self.format_tokens.iter_mut().flat_map(|token| &mut token.blocks).filter(|block| block.blink) {
+                    block.blink_on = !block.blink_on;
+                }
+            }
+        }
+        if self.no_animation {
+            for block in self.format_tokens.iter_mut().flat_map(|token| &mut token.blocks) {
+                block.curr_token = block.new_token.clone();
+            }
+            return false;
+        }
         let mut is_transitioning = false;
+        // Spring physics are integrated in 1ms steps to stay stable with stiff springs
+        let steps = duration.as_millis().max(1);
+        let step = duration.as_secs_f32() / steps as f32;
+        let elapsed = duration;
         let duration = duration.as_millis();
         for token in &mut self.format_tokens {
             for block in &mut token.blocks {
+                is_transitioning |= transition::age_particles(&mut block.particles);
                 if block.is_constant {
                     // continue
+                } else if self.typewriter && block.size > 1 {
+                    if block.new_token != block.curr_token {
+                        is_transitioning = true;
+                        let timing = Duration::from_millis(block.transition_timing as u64);
+                        let typewriter = block.typewriter.get_or_insert_with(TypewriterTransition::default);
+                        if typewriter.tick(elapsed, timing, &block.new_token) {
+                            block.typewriter = None;
+                            block.curr_token = block.new_token.clone();
+                        }
+                    }
+                } else if self.slot_machine {
+                    if block.new_token != block.curr_token {
+                        is_transitioning = true;
+                        let reel = block.slot_machine
+                            .get_or_insert_with(|| SlotMachineTransition::new(&block.curr_token, &block.new_token));
+                        if reel.tick(elapsed, &block.new_token) {
+                            block.slot_machine = None;
+                            block.curr_token = block.new_token.clone();
+                        }
+                    }
+                } else if let Some((g, restitution)) = self.gravity {
+                    if block.new_token != block.curr_token {
+                        is_transitioning = true;
+                        block.gravity.tick(elapsed, g, restitution);
+                        if block.gravity.is_settled() {
+                            block.gravity = GravityTransition::default();
+                            block.curr_token = block.new_token.clone();
+                        }
+                    }
+                } else if let Some((_, damping)) = self.spring {
+                    if block.new_token != block.curr_token {
+                        is_transitioning = true;
+                        for _ in 0..steps {
+                            let acceleration = block.spring_constant * (1.0 - block.position) - damping * block.velocity;
+                            block.velocity += acceleration * step;
+                            block.position += block.velocity * step;
+                        }
+                        if (block.position - 1.0).abs() < 0.001 && block.velocity.abs() < 0.01 {
+                            block.position = 0.0;
+                            block.velocity = 0.0;
+                            block.curr_token = block.new_token.clone();
+                        }
+                    }
                 } else if block.transition_progress > block.transition_timing {
                     block.transition_progress = 0;
+                    block.stagger_elapsed = 0;
                     block.curr_token = block.new_token.clone();
                 } else if block.new_token != block.curr_token {
                     is_transitioning = true;
-                    block.transition_progress += duration;
+                    // Waits for the digits before it to get going first
+                    if block.stagger_elapsed < block.stagger_delay {
+                        block.stagger_elapsed += duration;
+                    } else {
+                        block.transition_progress += duration;
+                    }
                 }
             }
         }
@@ -121,103 +788,2077 @@ impl AnimatedTime {
     }
 }
 
+/// What the application shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AppMode {
+    /// The time of day.
+    #[default]
+    Clock,
+    /// Time left on the `--countdown`.
+    Countdown,
+    /// Time counted by the `--stopwatch`.
+    Stopwatch,
+}
+
 /// Application.
 #[derive(Debug)]
 pub struct App {
     pub running: bool,
+    /// Number of logic ticks handled so far.
+    tick_counter: u64,
+    /// What the application shows.
+    mode: AppMode,
+    /// Whether the bell has rung for the end of the countdown.
+    has_rung_bell: bool,
+    /// Whether the borders are doubled up in the current frame of the flashing at the end of the
+    /// countdown.
+    is_border_flashed: bool,
     animated_time: AnimatedTime,
-    direction: u8
+    /// Edge new tokens wipe in from.
+    direction: WipeDirection,
+    /// Alternate the wipe direction between neighbouring blocks.
+    per_block_direction: bool,
+    /// Control points of the cubic Bézier easing applied to transitions.
+    transition_curve: Option<[f32; 4]>,
+    /// FIGfont the clock is drawn in, loaded once at startup.
+    font: Font,
+    /// Colour depth every colour is downgraded to.
+    color_depth: ColorDepth,
+    /// How the digits are coloured.
+    color_mode: ColorMode,
+    /// Time spent rendering so far, in seconds, driving the `--rainbow`.
+    render_elapsed: f64,
+    /// Formats `--smart-width` falls back on as the terminal narrows, widest first.
+    format_cascade: Vec<String>,
+    /// Terminal width the format was last fitted to by `--smart-width`.
+    fitted_width: Option<u16>,
+    /// Whether even the narrowest format is too wide, so the time is shown as plain text.
+    is_narrow: bool,
+    /// Milliseconds shown in a small font below the clock face by `--milliseconds`.
+    milliseconds: Option<AnimatedTime>,
+    /// Font of the `--milliseconds` row.
+    milliseconds_font: Font,
+    /// Whether digits are drawn without boxes, packed as tightly as their figures allow.
+    compact: bool,
+    /// Borders of the boxes around digits, or `None` to draw them without boxes.
+    border_type: Option<BorderType>,
+    /// Screen position of the mouse, if it has moved over the terminal.
+    mouse_position: Option<(u16, u16)>,
+    /// Where the clock face sits across the terminal.
+    align: HorizontalAlign,
+    /// Where the clock face sits down the terminal.
+    valign: VerticalAlign,
+    /// Whether the date is shown above the clock face.
+    show_date: bool,
+    /// `strftime` format string of the date.
+    date_format: String,
+    config: AppConfig,
+    /// Position within the brightness cycle of constant blocks, in milliseconds.
+    constant_phase: u128,
+    /// Style of constant blocks, pulsed by `--animate-constant`.
+    constant_style: Style,
+    /// Time last described on stdout by `--accessible`.
+    described_time: String,
+    /// Minute the time was last beeped out in Morse code.
+    morse_minute: Option<u32>,
+    /// Settings last applied from the `--config` file.
+    config_file: Option<ConfigFile>,
+    /// Modification time of the `--config` file when last applied.
+    config_file_modified: Option<SystemTime>,
+    /// When the `--config` file was last checked for changes.
+    config_file_polled: Instant,
+    /// How often the `--config` file is checked for changes.
+    config_poll_interval: Duration,
+    /// Colour of the clock set in the `--config` file.
+    config_color: Option<Color>,
+    /// Colours for calendar dates, if configured.
+    holiday_colors: Option<HolidayColorMap>,
+    /// Colour of the clock for today's date, if it has one.
+    holiday_color: Option<Color>,
+    /// Style sheet of the clock face, if configured.
+    style_file: Option<StyleFile>,
+    /// Colours are inverted until this time.
+    invert_until: Option<Instant>,
+    /// The clock flashes until this time.
+    flash_until: Option<Instant>,
+    /// ASCII art shown behind the clock by `--slideshow`, if any.
+    slideshow: Option<Slideshow>,
+    /// Background drawn by `--voronoi`, if enabled.
+    voronoi: Option<Voronoi>,
+    /// Angle each `--hypno` ring has turned through, innermost first, in radians.
+    hypno_phases: Vec<f64>,
+    /// Confetti thrown by `--confetti`.
+    confetti: Confetti,
+    /// Fireworks set off by `--fireworks`.
+    fireworks: Fireworks,
+    /// Lock screen guarding exit, if enabled.
+    pin_lock: Option<PinLock>,
+    /// Second of the minute at each of the most recent logic ticks, shown by `--sparkline-seconds`.
+    second_history: VecDeque<u64>,
+    /// Number of seconds shown in each of the most recent minutes, shown by `--barchart-seconds`.
+    minute_seconds: VecDeque<(String, u64)>,
+    /// Second last counted towards `minute_seconds`.
+    counted_second: Option<u32>,
+    /// Phase of the moon shown by `--moon-phase`, and the hour it was worked out in.
+    moon_phase: Option<(DateTime<FixedOffset>, (char, &'static str))>,
+    /// Sunrise and sunset shown by `--sunrise-sunset`, and the date they are for.
+    sun_times: Option<(NaiveDate, String)>,
+    /// Shell command run by `--watch`, if any.
+    watch: Option<Watch>,
+    /// Clock presets selectable as tabs by `--tabs`.
+    presets: Vec<Preset>,
+    /// Time of each preset. The active preset's time is swapped out into `animated_time`.
+    tab_times: Vec<AnimatedTime>,
+    /// Index of the active preset.
+    active_tab: usize,
+    /// Total stopwatch time at each lap recorded with `l`.
+    laps: Vec<Duration>,
+    /// Number of laps scrolled past in the lap panel.
+    lap_scroll: u16,
+    /// Labelled clocks of each `--timezone` shown together, when more than one is given.
+    world_clocks: Vec<(String, AnimatedTime)>,
+    /// World clocks removed with `-`, most recent last, to be added back with `+`.
+    removed_world_clocks: Vec<(String, AnimatedTime)>,
+    /// Measured precision of render ticks, shown by `--precision-mode`.
+    precision: TickPrecision,
+    /// Target interval between render ticks.
+    render_tick_rate: Duration,
+    /// When the last audio beat was detected.
+    #[cfg(feature = "beat-sync")]
+    last_beat: Option<Instant>,
+    #[cfg(feature = "clipboard")]
+    clipboard: TimeClipboard,
+    /// Headlines scrolled by `--news-ticker`, if set.
+    #[cfg(feature = "network")]
+    news_ticker: Option<NewsTicker>,
+    /// Current weather shown by `--weather`, if set.
+    #[cfg(feature = "network")]
+    weather: Option<Weather>,
+    /// Round trip time to the `--network-time-display` server, if set.
+    #[cfg(feature = "network")]
+    ntp_monitor: Option<NtpMonitor>,
+    /// Plays `--sound-on-hour`, if set.
+    #[cfg(feature = "audio")]
+    audio: Option<AudioPlayer>,
+    /// Hour the time was last in, for `--sound-on-hour`.
+    #[cfg(feature = "audio")]
+    sounded_hour: Option<u32>,
+    /// Whether the `--countdown-sound` is ticking.
+    #[cfg(feature = "audio")]
+    is_countdown_ticking: bool,
+    /// Whether the heap was over the `--memory-limit` when last checked.
+    #[cfg(feature = "mem-limit")]
+    is_over_memory_limit: bool,
+    /// Notice of the heap going over the `--memory-limit`, shown until the given time.
+    #[cfg(feature = "mem-limit")]
+    memory_notice: Option<(String, Instant)>,
 }
 
 impl Default for App {
     fn default() -> Self {
-        Self { running: true, animated_time: AnimatedTime::new(), direction: 0 }
+        Self {
+            running: true,
+            mode: AppMode::Clock,
+            has_rung_bell: false,
+            is_border_flashed: false,
+            tick_counter: 0,
+            animated_time: AnimatedTime::new(),
+            direction: WipeDirection::TopDown,
+            per_block_direction: false,
+            transition_curve: None,
+            font: Font::standard(),
+            color_depth: ColorDepth::TrueColor,
+            color_mode: ColorMode::Plain,
+            render_elapsed: 0.0,
+            format_cascade: Vec::new(),
+            fitted_width: None,
+            is_narrow: false,
+            milliseconds: None,
+            milliseconds_font: Font::small(),
+            compact: false,
+            border_type: Some(BorderType::Rounded),
+            mouse_position: None,
+            align: HorizontalAlign::Center,
+            valign: VerticalAlign::Middle,
+            show_date: false,
+            date_format: String::new(),
+            config: AppConfig::default(),
+            constant_phase: 0,
+            constant_style: Style::default(),
+            described_time: String::new(),
+            morse_minute: None,
+            config_file: None,
+            config_file_modified: None,
+            config_file_polled: Instant::now(),
+            config_poll_interval: CONFIG_POLL_INTERVAL,
+            config_color: None,
+            holiday_colors: None,
+            holiday_color: None,
+            style_file: None,
+            invert_until: None,
+            flash_until: None,
+            slideshow: None,
+            voronoi: None,
+            hypno_phases: Vec::new(),
+            confetti: Confetti::default(),
+            fireworks: Fireworks::default(),
+            pin_lock: None,
+            second_history: VecDeque::with_capacity(SPARKLINE_LENGTH),
+            minute_seconds: VecDeque::new(),
+            counted_second: None,
+            moon_phase: None,
+            sun_times: None,
+            watch: None,
+            presets: Vec::new(),
+            tab_times: Vec::new(),
+            active_tab: 0,
+            laps: Vec::new(),
+            lap_scroll: 0,
+            world_clocks: Vec::new(),
+            removed_world_clocks: Vec::new(),
+            precision: TickPrecision::default(),
+            render_tick_rate: Duration::ZERO,
+            #[cfg(feature = "beat-sync")]
+            last_beat: None,
+            #[cfg(feature = "clipboard")]
+            clipboard: TimeClipboard::default(),
+            #[cfg(feature = "network")]
+            news_ticker: None,
+            #[cfg(feature = "network")]
+            weather: None,
+            #[cfg(feature = "network")]
+            ntp_monitor: None,
+            #[cfg(feature = "audio")]
+            audio: None,
+            #[cfg(feature = "audio")]
+            sounded_hour: None,
+            #[cfg(feature = "audio")]
+            is_countdown_ticking: false,
+            #[cfg(feature = "mem-limit")]
+            is_over_memory_limit: false,
+            #[cfg(feature = "mem-limit")]
+            memory_notice: None,
+        }
     }
 }
 
 impl App {
     /// Constructs a new instance of [`App`].
-    pub fn new(transition_timing: u128) -> Self {
-        Self { animated_time: AnimatedTime::new().set_timing(transition_timing) , ..App::default() }
+    pub fn new(mut config: AppConfig) -> AppResult<Self> {
+        // Only the hash of the PIN is kept
+        let pin = config.pin.take();
+        let pin_lock = match config.lock_screen {
+            true => pin.as_deref().map(PinLock::new),
+            false => None
+        };
+        let font = match config.font.as_deref().map(Font::load) {
+            Some(Ok(font)) => font,
+            Some(Err(e)) => {
+                writeln!(io::stderr(), "{}, falling back to the standard font", e).ok();
+                Font::standard()
+            }
+            None => Font::standard()
+        };
+        let style_file = config.style_file.as_deref().map(StyleFile::load).transpose()?;
+        let holiday_colors = config.holiday_colors.as_deref().map(HolidayColorMap::load).transpose()?;
+        let holiday_color = holiday_colors.as_ref().and_then(|colors| colors.color_for(now_in(config.timezone.first().copied()).date_naive()));
+        let watch = config.watch.as_deref().map(|command| Watch::new(command, config.chart_history.unwrap_or(0) as usize));
+        let presets = config.tabs.as_deref().map(preset::load).transpose()?.unwrap_or_default();
+        let tab_times: Vec<AnimatedTime> = presets
+            .iter()
+            .map(|preset| {
+                let time = animated_time(&config, preset.font.as_ref().unwrap_or(&font)).set_format(&preset.format);
+                match preset.timezone {
+                    Some(timezone) => time.set_timezone(Some(timezone)),
+                    None => time
+                }
+            })
+            .collect();
+        let slideshow = config.slideshow
+            .as_deref()
+            .map(|dir| Slideshow::new(dir, Duration::from_secs(config.slideshow_interval)))
+            .transpose()?;
+        let world_clocks = match config.timezone.len() {
+            0 | 1 => Vec::new(),
+            _ => config.timezone
+                .iter()
+                .map(|&tz| (timezone_label(tz), animated_time(&config, &font).set_timezone(Some(tz)).set_format(config.format.as_deref().unwrap_or(DEFAULT_FORMAT))))
+                .collect()
+        };
+        #[cfg(feature = "network")]
+        let news_ticker = config.news_ticker.as_deref().map(NewsTicker::new);
+        #[cfg(feature = "network")]
+        let weather = config.weather.as_deref().map(Weather::new);
+        #[cfg(feature = "network")]
+        let ntp_monitor = config.network_time_display.as_deref().map(NtpMonitor::new);
+        #[cfg(feature = "audio")]
+        let audio = (config.sound_on_hour.is_some() || config.countdown_sound.is_some()).then(AudioPlayer::new);
+        let mut app = Self {
+            animated_time: match tab_times.first() {
+                Some(time) => time.clone(),
+                None if config.stopwatch => animated_time(&config, &font)
+                    .set_stopwatch(true)
+                    .set_format(config.format.as_deref().unwrap_or(config.stopwatch_precision.format())),
+                None if config.countdown.is_some() => animated_time(&config, &font)
+                    .set_countdown(config.countdown.map(|target| target.remaining()))
+                    .set_format(config.format.as_deref().unwrap_or("%H:%M:%S")),
+                None => animated_time(&config, &font).set_format(config.format.as_deref().unwrap_or(DEFAULT_FORMAT))
+            },
+            mode: match (config.stopwatch, config.countdown) {
+                (true, _) => AppMode::Stopwatch,
+                (_, Some(_)) => AppMode::Countdown,
+                _ => AppMode::Clock
+            },
+            voronoi: config.voronoi.then(|| Voronoi::new(config.voronoi_seeds as usize)),
+            hypno_phases: vec![0.0; config.hypno.unwrap_or(0) as usize],
+            // Stacked blocks are wider than they are tall, so wipe across them by default
+            direction: WipeDirection::from_index(config.direction.unwrap_or(config.vertical as u8)),
+            per_block_direction: config.dual_direction,
+            transition_curve: config.transition_curve,
+            // The first tab is shown first, in its own font
+            font: presets.first().and_then(|preset| preset.font.clone()).unwrap_or(font),
+            color_depth: config.color_depth(),
+            color_mode: match config.rainbow {
+                true => ColorMode::Rainbow { speed_hz: config.rainbow_speed },
+                false => ColorMode::Plain
+            },
+            milliseconds: config.milliseconds.then(|| AnimatedTime::new().set_font(Font::small()).set_no_animation(true).set_format("%3f")),
+            compact: config.compact,
+            border_type: config.border.border_type(),
+            mouse_position: None,
+            align: config.align,
+            valign: config.valign,
+            show_date: config.date,
+            date_format: config.date_format.clone(),
+            config,
+            holiday_colors,
+            holiday_color,
+            style_file,
+            watch,
+            presets,
+            tab_times,
+            world_clocks,
+            slideshow,
+            #[cfg(feature = "network")]
+            news_ticker,
+            #[cfg(feature = "network")]
+            weather,
+            #[cfg(feature = "network")]
+            ntp_monitor,
+            #[cfg(feature = "audio")]
+            audio,
+            pin_lock,
+            ..App::default()
+        };
+        if let Some(path) = app.config.config.clone() {
+            app.hot_reload_config(&path)?;
+        }
+        app.format_cascade = format_cascade(&app.animated_time.format_string());
+        Ok(app)
     }
 
-    /// Handles the tick event of the terminal.
-    pub fn tick_logic(&mut self, duration: Duration, event: &EventHandler) {
-        self.animated_time.tick_logic();
-        event.trigger_animation(true);
+    /// Reloads the `--config` file at `path`, applying whatever changed since it was last loaded.
+    pub fn hot_reload_config(&mut self, path: &Path) -> AppResult<()> {
+        let modified = fs::metadata(path)?.modified().ok();
+        let file = ConfigFile::load(path)?;
+        let color = file.color.as_deref().map(parse_color).transpose()?;
+        let previous = self.config_file.take().unwrap_or_default();
+        if file.format != previous.format {
+            let format = file.format.as_deref().or(self.config.format.as_deref()).unwrap_or(DEFAULT_FORMAT);
+            self.animated_time = self.animated_time.clone().set_format(format);
+        }
+        if file.transition_timing != previous.transition_timing {
+            let timing = file.transition_timing.map_or(self.config.transition_timing, u128::from);
+            self.animated_time = self.animated_time.clone().set_timing(timing);
+        }
+        if file.font != previous.font {
+            match file.font.as_deref().or(self.config.font.as_deref()).map_or_else(|| Ok(Font::standard()), Font::load) {
+                Ok(font) => {
+                    self.animated_time = self.animated_time.clone().set_font(font.clone());
+                    self.font = font;
+                }
+                Err(e) => {
+                    writeln!(io::stderr(), "{}, keeping the current font", e).ok();
+                }
+            }
+        }
+        #[cfg(feature = "debug")]
+        tracing::info!(path = %path.display(), ?file, "reloaded config file");
+        self.config_color = color;
+        self.config_file = Some(file);
+        self.config_file_modified = modified;
+        Ok(())
     }
 
-    pub fn tick_render(&mut self, duration: Duration, event: &EventHandler) {
-        let is_transitioning = self.animated_time.tick_render(duration);
-        event.trigger_animation(is_transitioning);
+    /// The configuration the application was started with.
+    pub fn config(&self) -> &AppConfig {
+        &self.config
     }
 
-    /// Renders the user interface widgets.
-    pub fn render<B: Backend>(&mut self, frame: &mut Frame<'_, B>) {
-        // This is where you add new widgets.
-        // See the following resources:
-        // - https://docs.rs/tui/0.16.0/tui/widgets/index.html
-        // - https://github.com/fdehau/tui-rs/tree/v0.16.0/examples
-        let mut constraints: Vec<Constraint> = Vec::new();
-        let mut width: usize = 0;
-        for tokens in &self.animated_time.format_tokens {
-            for block in &tokens.blocks {
-                let size = block.size * match block.is_constant {
-                    true => 8,
-                    false => 15
-                };
-                constraints.push(Constraint::Length(size as u16));
-                width += size
+    /// Sets how often the `--config` file is checked for changes, every 10 seconds by default.
+    pub fn set_config_poll_interval(&mut self, interval: Duration) {
+        self.config_poll_interval = interval;
+    }
+
+    /// Sets the target interval between render ticks, shown by `--precision-mode`.
+    pub fn set_render_tick_rate(&mut self, render_tick_rate: Duration) {
+        self.render_tick_rate = render_tick_rate;
+    }
+
+    /// Switches the clock to a new format string, transitioning every block in.
+    pub fn set_format(&mut self, format: &str) {
+        self.animated_time = self.animated_time.clone().set_format(format);
+        for block in self.animated_time.format_tokens.iter_mut().flat_map(|token| &mut token.blocks) {
+            if !block.is_constant {
+                block.curr_token.clear();
             }
         }
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(constraints.as_slice())
-            .horizontal_margin((frame.size().width - width as u16) / 2)
-            .vertical_margin((frame.size().height - 9) / 2)
-            .split(frame.size());
-        let standard_font = FIGfont::standand().unwrap();
-        let transition_box = Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded);
-        let digit_box = Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded);
-        let mut i = 0;
-        for tokens in &self.animated_time.format_tokens {
-            for block in &tokens.blocks {
-                let figure = match standard_font.convert(&block.curr_token) {
-                    Some(figure) => figure,
-                    None => standard_font.convert(" ").unwrap()
-                };
-                frame.render_widget(Paragraph::new(format!("\n\n{}", figure)).alignment(Alignment::Center), chunks[i]);
-                if !block.is_constant {
-                    frame.render_widget(digit_box.clone(), chunks[i]);
-                }
-                if block.transition_progress > 0 {
-                    let mut direction = Direction::Vertical;
-                    if (self.direction % 2) == 1 {
-                        direction = Direction::Horizontal;
-                    }
-                    let (constraint, chunk_index) = {
-                        let constraint =  (((100 * block.transition_progress) / block.transition_timing) as u16).min(100);
-                        if self.direction > 1 {
-                            ([Constraint::Percentage(100 - constraint), Constraint::Percentage(constraint)], 1)
-                        } else {
-                            ([Constraint::Percentage(constraint), Constraint::Percentage(0)], 0)
-                        }
-                    };
-                    let chunks = Layout::default()
-                        .direction(direction)
-                        .constraints(constraint)
-                        .split(chunks[i]);
-                    frame.render_widget(Clear, chunks[chunk_index]);
-                    let figure = standard_font.convert(&block.new_token).unwrap().to_string();
-                    frame.render_widget(transition_box.clone(), chunks[chunk_index]);
-                    frame.render_widget(Paragraph::new(format!("\n\n{}", figure)).alignment(Alignment::Center), chunks[chunk_index]);
-                }
-                i += 1
+        self.format_cascade = format_cascade(format);
+        self.fitted_width = None;
+    }
+
+    /// Switches to the widest format of the `--smart-width` cascade that fits in `width` cells,
+    /// or to plain text if none do.
+    fn fit_format(&mut self, width: u16) {
+        if !self.config.smart_width || self.fitted_width == Some(width) || self.format_cascade.is_empty() {
+            return;
+        }
+        self.fitted_width = Some(width);
+        let time = self.animated_time.clone();
+        for format in self.format_cascade.clone() {
+            self.animated_time = time.clone().set_format(&format);
+            if self.face_size().0 <= width {
+                self.is_narrow = false;
+                return;
             }
         }
+        self.animated_time = time.set_format(&self.format_cascade[0]);
+        self.is_narrow = true;
+    }
+
+    /// What the application shows.
+    pub fn mode(&self) -> AppMode {
+        self.mode
+    }
+
+    /// Sets whether the main loop keeps running.
+    pub fn set_running(&mut self, running: bool) {
+        self.running = running;
+    }
+
+    /// Switches to the next preset tab, if any.
+    pub fn next_tab(&mut self) {
+        if self.tab_times.is_empty() {
+            return;
+        }
+        std::mem::swap(&mut self.animated_time, &mut self.tab_times[self.active_tab]);
+        self.active_tab = (self.active_tab + 1) % self.tab_times.len();
+        std::mem::swap(&mut self.animated_time, &mut self.tab_times[self.active_tab]);
+        // Each tab's time keeps the font it's drawn in
+        if let Some(font) = &self.animated_time.font {
+            self.font = font.clone();
+        }
+    }
+
+    /// Turns the edge new tokens wipe in from by `steps` quarter turns.
+    pub fn turn_direction(&mut self, steps: i8) {
+        self.direction = self.direction.turn(steps);
+    }
+
+    /// Removes the last clock of the world clock, keeping at least one.
+    pub fn remove_world_clock(&mut self) {
+        if self.world_clocks.len() > 1 {
+            self.removed_world_clocks.extend(self.world_clocks.pop());
+        }
+    }
+
+    /// Adds back the world clock most recently removed, if any.
+    pub fn add_world_clock(&mut self) {
+        self.world_clocks.extend(self.removed_world_clocks.pop());
+    }
+
+    /// Exits the application, or asks for the PIN on the lock screen.
+    ///
+    /// Does nothing in kiosk mode.
+    pub fn quit(&mut self) {
+        if self.config.kiosk {
+            return;
+        }
+        match &mut self.pin_lock {
+            Some(lock) => lock.open(),
+            None => self.running = false
+        }
+    }
+
+    /// The lock screen, if its PIN dialog is open.
+    pub fn pin_dialog(&mut self) -> Option<&mut PinLock> {
+        self.pin_lock.as_mut().filter(|lock| lock.entry().is_some())
     }
+
+    /// Handles the tick event of the terminal.
+    pub fn tick_logic(&mut self, duration: Duration, event: &impl AnimationTrigger) {
+        self.tick_counter = self.tick_counter.wrapping_add(1);
+        self.animated_time.tick_logic();
+        for (_, time) in &mut self.world_clocks {
+            time.tick_logic();
+        }
+        if let Some(voronoi) = &mut self.voronoi {
+            voronoi.drift(duration);
+        }
+        if let Some(slideshow) = &mut self.slideshow {
+            slideshow.tick();
+        }
+        if self.mode == AppMode::Stopwatch {
+            // Keep up with the stopwatch while it runs, then drop back to the usual rate
+            let render_tick_rate = match self.is_stopwatch_running() {
+                true => self.config.stopwatch_precision.render_tick_rate(),
+                false => self.render_rate()
+            };
+            self.render_tick_rate = Duration::from_millis(self.capped_render_tick_rate(render_tick_rate));
+            event.set_render_rate(self.render_tick_rate);
+        }
+        let now = self.now();
+        // Several ticks land in the same second, only the first one starts the inversion
+        if now.minute() == 0 && now.second() == 0
+            && ((now.hour() == 12 && self.config.invert_colors_at_noon)
+                || (now.hour() == 0 && self.config.invert_colors_at_midnight))
+            && self.invert_until.is_none_or(|until| Instant::now() >= until)
+        {
+            self.invert_until = Some(Instant::now() + INVERT_DURATION);
+        }
+        // The flash is over before the second is, so wait out the rest of the second before flashing again
+        if self.config.flash_on_hour && now.minute() == 0 && now.second() == 0
+            && self.flash_until.is_none_or(|until| Instant::now() >= until + Duration::from_secs(1))
+        {
+            self.flash_until = Some(Instant::now() + FLASH_DURATION);
+        }
+        // Several ticks land in the same second, so fireworks and confetti ignore all but the first
+        if self.config.fireworks && now.minute() == 0 && now.second() == 0 {
+            self.fireworks.launch();
+        }
+        if self.config.confetti && now.second() == 0 {
+            self.confetti.burst();
+        }
+        if self.config.sparkline_seconds {
+            if self.second_history.len() == SPARKLINE_LENGTH {
+                self.second_history.pop_front();
+            }
+            self.second_history.push_back(now.second() as u64);
+        }
+        if let Some(minutes) = self.config.barchart_seconds {
+            self.count_second(&now, minutes as usize);
+        }
+        if self.config.moon_phase {
+            self.update_moon_phase(&now);
+        }
+        if let (true, Some(location)) = (self.config.sunrise_sunset, self.config.location) {
+            self.update_sun_times(&now, location);
+        }
+        if let Some(watch) = &mut self.watch {
+            // A failing command just leaves the last output up
+            watch.tick().ok();
+        }
+        if let Some(path) = self.config.config.clone() {
+            if self.config_file_polled.elapsed() >= self.config_poll_interval {
+                self.config_file_polled = Instant::now();
+                if fs::metadata(&path).and_then(|metadata| metadata.modified()).ok() != self.config_file_modified {
+                    // Keep the current settings while the file is being edited into a valid state
+                    self.hot_reload_config(&path).ok();
+                }
+            }
+        }
+        if let Some(colors) = &mut self.holiday_colors {
+            // Keep the previous colours while the file is being edited into a valid state
+            colors.reload_if_changed().ok();
+            self.holiday_color = colors.color_for(now.date_naive());
+        }
+        if let Some(style_file) = &mut self.style_file {
+            // Keep the previous styles while the file is being edited into a valid state
+            style_file.reload_if_changed().ok();
+        }
+        if self.config.accessible {
+            let time = self.formatted_time();
+            if time != self.described_time {
+                // Kept off stderr, which the clock is drawn on
+                writeln!(io::stdout(), "Current time: {}", time).and_then(|_| io::stdout().flush()).ok();
+                self.described_time = time;
+            }
+        }
+        if self.config.morse_audio {
+            if self.morse_minute.is_some_and(|minute| minute != now.minute()) {
+                morse::play_bell(morse::morse_schedule(&now.format("%H:%M").to_string()));
+            }
+            self.morse_minute = Some(now.minute());
+        }
+        #[cfg(feature = "audio")]
+        if let (Some(audio), Some(path)) = (&self.audio, &self.config.sound_on_hour) {
+            if self.sounded_hour.is_some_and(|hour| hour != now.hour()) {
+                audio.play(path);
+            }
+            self.sounded_hour = Some(now.hour());
+        }
+        #[cfg(feature = "audio")]
+        self.tick_countdown_sound();
+        if self.is_countdown_over() && !self.has_rung_bell {
+            self.has_rung_bell = true;
+            // Rung on the terminal the interface is drawn to
+            write!(io::stderr(), "\x07").and_then(|_| io::stderr().flush()).ok();
+        }
+        #[cfg(feature = "clipboard")]
+        if self.config.clipboard {
+            self.clipboard.update(&self.formatted_time());
+        }
+        #[cfg(feature = "mem-limit")]
+        if let Some(limit) = self.config.memory_limit {
+            self.check_memory_limit(limit);
+        }
+        event.trigger_blink(self.config.blink_separator);
+        // Without transitions there is nothing to animate between logic ticks
+        if !self.config.no_animation {
+            event.trigger_animation(true);
+        }
+    }
+
+    /// Trims memory if the heap has grown past `limit` megabytes, noting it on the bottom row of
+    /// the screen each time it crosses the limit.
+    #[cfg(feature = "mem-limit")]
+    fn check_memory_limit(&mut self, limit: u64) {
+        let limit = limit as usize * 1024 * 1024;
+        if memory::allocated() <= limit {
+            self.is_over_memory_limit = false;
+            return;
+        }
+        self.trim_memory();
+        let allocated = memory::allocated();
+        if !self.is_over_memory_limit {
+            let notice = format!("Heap usage went over the memory limit of {} MB, trimmed to {} MB", limit / (1024 * 1024), allocated / (1024 * 1024));
+            #[cfg(feature = "debug")]
+            tracing::warn!("{}", notice);
+            self.memory_notice = Some((notice, Instant::now() + MEMORY_NOTICE_DURATION));
+        }
+        self.is_over_memory_limit = allocated > limit;
+    }
+
+    /// Draws the `--memory-limit` notice on the bottom row of the screen, while it lasts.
+    #[cfg(feature = "mem-limit")]
+    fn render_memory_notice<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let Some((notice, _)) = self.memory_notice.as_ref().filter(|(_, until)| Instant::now() < *until) else { return };
+        let area = frame.size();
+        if area.height > 0 {
+            let bottom = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+            frame.render_widget(Clear, bottom);
+            frame.render_widget(Paragraph::new(notice.as_str()).style(self.base_style()), bottom);
+        }
+    }
+
+    /// Number of particles, confetti pieces and history entries [`App::trim_memory`] would free.
+    #[cfg(feature = "mem-limit")]
+    pub fn trimmable_len(&self) -> usize {
+        let particles: usize = self.tab_times.iter()
+            .chain(self.world_clocks.iter().map(|(_, time)| time))
+            .chain([&self.animated_time])
+            .flat_map(|time| &time.format_tokens)
+            .flat_map(|tokens| &tokens.blocks)
+            .map(|block| block.particles.len())
+            .sum();
+        let watched = self.watch.as_ref().map_or(0, |watch| watch.history().len());
+        particles + self.confetti.pieces.len() + self.second_history.len() + self.minute_seconds.len() + watched
+    }
+
+    /// Frees what can be rebuilt or done without: transition particles, confetti and the
+    /// histories behind the charts.
+    pub fn trim_memory(&mut self) {
+        self.animated_time.clear_particles();
+        for time in self.tab_times.iter_mut().chain(self.world_clocks.iter_mut().map(|(_, time)| time)) {
+            time.clear_particles();
+        }
+        self.confetti.pieces = Vec::new();
+        self.second_history = VecDeque::with_capacity(SPARKLINE_LENGTH);
+        self.minute_seconds = VecDeque::new();
+        if let Some(watch) = &mut self.watch {
+            watch.clear_history();
+        }
+    }
+
+    /// Whether the `--stopwatch` is counting.
+    fn is_stopwatch_running(&self) -> bool {
+        self.animated_time.stopwatch().is_some_and(|stopwatch| stopwatch.is_running())
+    }
+
+    /// Pauses the stopwatch if it is running, or resumes it if paused.
+    pub fn toggle_stopwatch(&mut self) {
+        if let Some(stopwatch) = self.animated_time.stopwatch_mut() {
+            stopwatch.toggle();
+        }
+    }
+
+    /// Sets the stopwatch back to zero, clearing its laps.
+    pub fn reset_stopwatch(&mut self) {
+        if let Some(stopwatch) = self.animated_time.stopwatch_mut() {
+            stopwatch.reset();
+            self.laps.clear();
+            self.lap_scroll = 0;
+            self.animated_time.tick_logic();
+        }
+    }
+
+    /// Records a lap at the current stopwatch time.
+    pub fn record_lap(&mut self) {
+        if let Some(stopwatch) = self.animated_time.stopwatch() {
+            self.laps.push(stopwatch.elapsed());
+        }
+    }
+
+    /// Scrolls the lap panel by `laps`, towards later laps if positive.
+    pub fn scroll_laps(&mut self, laps: i16) {
+        let max_scroll = self.laps.len().saturating_sub(1) as u16;
+        self.lap_scroll = self.lap_scroll.saturating_add_signed(laps).min(max_scroll);
+    }
+
+    /// Whether the `--countdown` has reached zero.
+    fn is_countdown_over(&self) -> bool {
+        self.mode == AppMode::Countdown && self.animated_time.countdown_remaining().is_some_and(|remaining| remaining.is_zero())
+    }
+
+    /// Keeps the `--countdown-sound` ticking through the last seconds of the countdown, speeding
+    /// up from 1x with `--countdown-warn-seconds` left to 4x with one second left.
+    #[cfg(feature = "audio")]
+    fn tick_countdown_sound(&mut self) {
+        let (Some(audio), Some(path), Some(remaining)) =
+            (&self.audio, &self.config.countdown_sound, self.animated_time.countdown_remaining()) else {
+            return;
+        };
+        let warn = self.config.countdown_warn_seconds as f32;
+        let remaining = remaining.as_secs_f32();
+        if remaining > 0.0 && remaining <= warn {
+            if !self.is_countdown_ticking {
+                audio.start_loop(path);
+                self.is_countdown_ticking = true;
+            }
+            let urgency = ((warn - remaining) / (warn - 1.0).max(1.0)).clamp(0.0, 1.0);
+            audio.set_loop_speed(1.0 + 3.0 * urgency);
+        } else if self.is_countdown_ticking {
+            audio.stop_loop();
+            self.is_countdown_ticking = false;
+        }
+    }
+
+    /// Copies the current time to the system clipboard.
+    #[cfg(feature = "clipboard")]
+    pub fn copy_time(&mut self) {
+        self.clipboard.copy(&self.formatted_time());
+    }
+
+    /// The displayed time as plain text.
+    pub fn formatted_time(&self) -> String {
+        self.animated_time.formatted()
+    }
+
+    pub fn tick_render(&mut self, duration: Duration, event: &impl AnimationTrigger) {
+        if self.config.precision_mode {
+            self.precision.record(duration);
+        }
+        if self.is_stopwatch_running() {
+            // The stopwatch changes faster than logic ticks come in
+            self.animated_time.tick_logic();
+        }
+        let is_transitioning = self.animated_time.tick_render(duration) || self.is_stopwatch_running();
+        // The borders keep flashing once the countdown is over
+        if self.is_countdown_over() {
+            self.is_border_flashed = !self.is_border_flashed;
+        }
+        let is_transitioning = is_transitioning || self.is_countdown_over();
+        // Milliseconds change faster than any tick comes in, so they're always animating
+        let is_transitioning = match &mut self.milliseconds {
+            Some(milliseconds) => {
+                milliseconds.tick_logic();
+                milliseconds.tick_render(duration);
+                true
+            }
+            None => is_transitioning
+        };
+        let is_transitioning = self.world_clocks.iter_mut().fold(is_transitioning, |is_transitioning, (_, time)| time.tick_render(duration) || is_transitioning);
+        #[cfg(feature = "debug")]
+        for (block_index, block) in self.animated_time.format_tokens.iter().flat_map(|tokens| &tokens.blocks).enumerate() {
+            if let Some(transition_progress) = self.animated_time.transition_progress(block) {
+                tracing::trace!(block_index, transition_progress, token = %block.new_token, "transition");
+            }
+        }
+        let is_transitioning = is_transitioning || self.pulse_constants(duration);
+        let is_transitioning = self.spin_hypno(duration) || is_transitioning;
+        // The second hand, progress ring and progress bar sweep continuously
+        let is_transitioning = is_transitioning || self.config.circle_clock || self.config.progress_ring.is_some() || self.config.progress_bar;
+        self.render_elapsed += duration.as_secs_f64();
+        let is_transitioning = is_transitioning || self.color_mode != ColorMode::Plain;
+        let is_transitioning = self.confetti.tick(duration) || is_transitioning;
+        let is_transitioning = self.fireworks.tick() || is_transitioning;
+        #[cfg(feature = "network")]
+        let is_transitioning = self.news_ticker.as_mut().is_some_and(|ticker| ticker.tick(duration)) || is_transitioning;
+        #[cfg(feature = "beat-sync")]
+        let is_transitioning = is_transitioning || self.beat_intensity().is_some();
+        event.trigger_animation(is_transitioning);
+    }
+
+    /// Turns each `--hypno` ring, slowing from the innermost to the outermost, returning whether
+    /// there are any.
+    fn spin_hypno(&mut self, duration: Duration) -> bool {
+        let rings = self.hypno_phases.len();
+        for (i, phase) in self.hypno_phases.iter_mut().enumerate() {
+            let outwardness = match rings {
+                1 => 0.0,
+                _ => i as f64 / (rings - 1) as f64
+            };
+            let rpm = HYPNO_INNER_RPM + (HYPNO_OUTER_RPM - HYPNO_INNER_RPM) * outwardness;
+            *phase = (*phase + std::f64::consts::TAU * rpm / 60.0 * duration.as_secs_f64()) % std::f64::consts::TAU;
+        }
+        rings > 0
+    }
+
+    /// Advances the brightness cycle of constant blocks, returning whether they are animated.
+    fn pulse_constants(&mut self, duration: Duration) -> bool {
+        if !self.config.animate_constant {
+            return false;
+        }
+        let period = self.config.animate_constant_period.max(1);
+        self.constant_phase = (self.constant_phase + duration.as_millis()) % period;
+        let angle = std::f32::consts::TAU * self.constant_phase as f32 / period as f32;
+        let level = 96 + (159.0 * (0.5 + 0.5 * angle.sin())) as u8;
+        self.constant_style = Style::default().fg(Color::Rgb(level, level, level));
+        true
+    }
+
+    /// Handles an audio beat, lighting up the border.
+    #[cfg(feature = "beat-sync")]
+    pub fn beat(&mut self, event: &impl AnimationTrigger) {
+        self.last_beat = Some(Instant::now());
+        event.trigger_animation(true);
+    }
+
+    /// How lit the border is from the last beat, fading from 1.0 to 0.0.
+    #[cfg(feature = "beat-sync")]
+    fn beat_intensity(&self) -> Option<f32> {
+        let elapsed = self.last_beat?.elapsed();
+        (elapsed < BEAT_PULSE).then(|| 1.0 - elapsed.as_secs_f32() / BEAT_PULSE.as_secs_f32())
+    }
+
+    /// Writes the current clock face to `path` as an SVG document.
+    pub fn export_svg(&self, path: &Path) -> AppResult<()> {
+        fs::write(path, export::svg(&self.export_blocks(), self.face_height()))?;
+        Ok(())
+    }
+
+    /// Draws `frames` frames off screen, animating between them, and returns how many frames per
+    /// second were drawn.
+    pub fn benchmark(&mut self, frames: u32) -> AppResult<f64> {
+        let mut terminal = Terminal::new(TestBackend::new(BENCHMARK_SIZE.0, BENCHMARK_SIZE.1))?;
+        let tick = Duration::from_millis(RENDER_TICK_RATE);
+        let started = Instant::now();
+        for _ in 0..frames {
+            // The time rarely changes during a benchmark, so keep the digits transitioning
+            if !self.animated_time.is_transitioning() {
+                self.animated_time.advance_digits();
+            }
+            self.tick_render(tick, &NoopAnimationTrigger);
+            terminal.draw(|frame| self.render(frame))?;
+        }
+        Ok(frames as f64 / started.elapsed().as_secs_f64())
+    }
+
+    /// Number of digit blocks the time is drawn with, each converted to a figure every frame.
+    pub fn format_tokens_count(&self) -> usize {
+        self.animated_time.format_tokens.iter().map(|tokens| tokens.blocks.len()).sum()
+    }
+
+    /// Frames per second to render at when `--frame-rate-limit` isn't given.
+    ///
+    /// Every block is converted to a figure on each frame, so the cost of a frame grows with the
+    /// number of blocks. Spending at most `FRAME_BUDGET` of each second rendering keeps CPU usage
+    /// under about 5%, which allows `FRAME_BUDGET / (BLOCK_FRAME_COST * blocks)` frames a second:
+    /// 250 fps at one block, 31 fps for `%X`'s eight. That's capped at the render rate and
+    /// floored at `MIN_DEFAULT_FPS`.
+    pub fn default_frame_rate(&self) -> u64 {
+        let max_fps = 1000 / self.render_rate();
+        let blocks = self.format_tokens_count().max(1) as f64;
+        ((FRAME_BUDGET / (BLOCK_FRAME_COST * blocks)) as u64).clamp(MIN_DEFAULT_FPS, max_fps)
+    }
+
+    /// Frames per second rendered at most, from `--frame-rate-limit`, or from `--render-rate` or
+    /// the format when it isn't given.
+    pub fn frame_rate_limit(&self) -> u64 {
+        match (self.config.frame_rate_limit, self.config.render_rate) {
+            (Some(fps), _) => fps,
+            (None, Some(render_rate)) => (1000 / render_rate).max(1),
+            (None, None) => self.default_frame_rate()
+        }
+    }
+
+    /// Milliseconds between frames while animating, from `--render-rate`.
+    pub fn render_rate(&self) -> u64 {
+        self.config.render_rate.unwrap_or(RENDER_TICK_RATE)
+    }
+
+    /// `render_tick_rate` slowed down to keep within the frame rate limit, in milliseconds.
+    pub fn capped_render_tick_rate(&self, render_tick_rate: u64) -> u64 {
+        render_tick_rate.max(1000 / self.frame_rate_limit())
+    }
+
+    /// Format string the time is displayed with.
+    pub fn format_string(&self) -> String {
+        self.animated_time.format_string()
+    }
+
+    /// Follows the mouse, for showing the `--tooltip-on-hover` of the block under it.
+    pub fn handle_mouse(&mut self, event: MouseEvent) {
+        match event.kind {
+            MouseEventKind::Moved | MouseEventKind::Drag(_) => self.mouse_position = Some((event.column, event.row)),
+            _ => {}
+        }
+    }
+
+    /// Time drawn on the clock face.
+    pub fn animated_time(&self) -> &AnimatedTime {
+        &self.animated_time
+    }
+
+    /// Milliseconds drawn below the clock face by `--milliseconds`.
+    pub fn milliseconds(&self) -> Option<&AnimatedTime> {
+        self.milliseconds.as_ref()
+    }
+
+    /// The current time in the clock's `--timezone`, or the local timezone.
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        now_in(self.animated_time.timezone)
+    }
+
+    /// Name or path of the font the clock is drawn in.
+    pub fn font_name(&self) -> &str {
+        self.font.name()
+    }
+
+    /// Writes the current clock face to `path` as a PNG image.
+    pub fn export_png(&mut self, path: &Path) -> AppResult<()> {
+        let (width, height) = self.face_size();
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        terminal.draw(|frame| self.render(frame))?;
+        export::png(terminal.backend().buffer(), self.config.export_scale)
+            .save_with_format(path, ImageFormat::Png)?;
+        Ok(())
+    }
+
+    /// Lays out the current clock face for export.
+    fn export_blocks(&self) -> Vec<ExportBlock> {
+        self.animated_time.format_tokens.iter()
+            .flat_map(|token| &token.blocks)
+            .map(|block| ExportBlock {
+                figure: match self.font.convert(&block.curr_token) {
+                    Some(figure) => self.face_text(figure),
+                    None => String::new()
+                },
+                width: self.block_width(block),
+                bordered: !block.is_constant && self.is_bordered(),
+            })
+            .collect()
+    }
+
+    /// Style the whole clock face is drawn in.
+    fn base_style(&self) -> Style {
+        let preset_color = self.presets.get(self.active_tab).and_then(|preset| preset.color);
+        match self.holiday_color.or(preset_color).or(self.config_color) {
+            Some(color) => Style::default().fg(color),
+            None => Style::default()
+        }
+    }
+
+    /// Factor digits are scaled up by.
+    fn scale(&self) -> u16 {
+        match self.config.large_print {
+            true => 2,
+            false => 1
+        }
+    }
+
+    /// Whether digits are drawn in boxes.
+    fn is_bordered(&self) -> bool {
+        !self.config.no_border && !self.compact && self.digit_border_type().is_some()
+    }
+
+    /// The `--style-file` style sheet, or an empty one without it.
+    pub fn style_sheet(&self) -> StyleSheet {
+        self.style_file.as_ref().map(|file| *file.sheet()).unwrap_or_default()
+    }
+
+    /// Borders of the digit boxes, from the style sheet or `--border`.
+    fn digit_border_type(&self) -> Option<BorderType> {
+        self.style_sheet().digit.border_type().unwrap_or(self.border_type)
+    }
+
+    /// Width of a block on screen, in terminal cells.
+    fn block_width(&self, block: &TokenBlock) -> u16 {
+        let width = match (self.compact, block.is_constant) {
+            (false, _) => block.width(),
+            // Constants never change, so they can be exactly as wide as their figure
+            (true, true) => self.font
+                .convert(&block.new_token)
+                .and_then(|figure| figure.to_string().lines().map(|line| line.chars().count()).max())
+                .unwrap_or(0) as u16,
+            (true, false) => block.size as u16 * COMPACT_DIGIT_WIDTH
+        };
+        width * self.scale()
+    }
+
+    /// Height of the clock face on screen, in terminal cells.
+    fn face_height(&self) -> u16 {
+        match self.compact {
+            true => COMPACT_FACE_HEIGHT * self.scale(),
+            false => FACE_HEIGHT * self.scale()
+        }
+    }
+
+    /// Lays out the FIGfont rendering of a token for drawing within its block.
+    fn face_text(&self, figure: impl Display) -> String {
+        // Boxed figures sit clear of the top border
+        let padding = match self.compact {
+            true => "",
+            false => "\n\n"
+        };
+        font::scale_figfont(&format!("{}{}", padding, figure), self.scale() as u8)
+    }
+
+    /// Splits the blocks of the clock face into rows of `(index, width)` pairs, followed by the
+    /// timezone offset if shown.
+    ///
+    /// With `--two-line` the blocks before the first separator go on the top row and those after it
+    /// on the bottom row, leaving out the separator itself. With `--vertical` every block gets a row
+    /// of its own.
+    fn rows(&self) -> Vec<Vec<(usize, u16)>> {
+        let blocks: Vec<&TokenBlock> = self.animated_time.format_tokens.iter().flat_map(|tokens| &tokens.blocks).collect();
+        let row = |range: std::ops::Range<usize>| range.map(|i| (i, self.block_width(blocks[i]))).collect::<Vec<_>>();
+        let separator = blocks.iter().position(|block| block.is_constant).filter(|_| self.config.two_line);
+        let mut rows = match separator {
+            _ if self.config.vertical => (0..blocks.len()).map(|i| row(i..i + 1)).collect(),
+            Some(separator) => vec![row(0..separator), row(separator + 1..blocks.len())],
+            None => vec![row(0..blocks.len())]
+        };
+        if self.config.show_timezone_offset {
+            rows.last_mut().unwrap().push((blocks.len(), TIMEZONE_OFFSET_WIDTH));
+        }
+        rows
+    }
+
+    /// Width and height of the clock face on screen, in terminal cells.
+    fn face_size(&self) -> (u16, u16) {
+        let rows = self.rows();
+        let width = rows.iter().map(|row| row.iter().map(|&(_, width)| width).sum()).max().unwrap_or(0);
+        (width, self.face_height() * rows.len() as u16)
+    }
+
+    /// Lays out the blocks of the clock face within `area`, followed by the timezone offset if shown.
+    ///
+    /// Blocks left out of every row get an empty area.
+    fn layout(&self, area: Rect) -> Vec<Rect> {
+        let rows = self.rows();
+        // Placed together with the rows below the face, which the trailing constraint leaves room for
+        let face = self.align(area, area.width, self.face_height() * rows.len() as u16 + self.below_face_height());
+        let row_areas = Layout::default()
+            .direction(Direction::Vertical)
+            // The trailing constraint takes up any slack so every row is exactly one face tall
+            .constraints([vec![Constraint::Length(self.face_height()); rows.len()], vec![Constraint::Min(0)]].concat())
+            .split(face);
+        let blocks = self.animated_time.format_tokens.iter().map(|tokens| tokens.blocks.len()).sum::<usize>();
+        let mut chunks = vec![Rect::default(); blocks + self.config.show_timezone_offset as usize];
+        for (row, row_area) in rows.iter().zip(row_areas) {
+            let width: u16 = row.iter().map(|&(_, width)| width).sum();
+            let constraints: Vec<Constraint> = row.iter().map(|&(_, width)| Constraint::Length(width)).collect();
+            let row_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(constraints)
+                .split(self.align(row_area, width, row_area.height));
+            for (&(i, _), chunk) in row.iter().zip(row_chunks) {
+                chunks[i] = chunk;
+            }
+        }
+        chunks
+    }
+
+    /// Whether a `width` by `height` terminal has room for the clock.
+    ///
+    /// Only the digit face needs a minimum size, that of the face as currently formatted along
+    /// with the rows and panels drawn around it. The circle clock, table and progress ring scale
+    /// to fit.
+    pub fn check_size(&self, width: u16, height: u16) -> bool {
+        if self.config.circle_clock || self.config.table_mode || self.config.progress_ring.is_some() {
+            return true;
+        }
+        // Too narrow even for `--smart-width`, so shown as a line of plain text
+        if self.is_narrow {
+            return height >= 1;
+        }
+        let (face_width, face_height) = self.face_size();
+        let (required_width, required_height) = match self.world_clocks.len() {
+            0 => {
+                let date_height = if self.show_date { DATE_HEIGHT } else { 0 };
+                let laps_width = if self.laps.is_empty() { 0 } else { LAP_PANEL_WIDTH };
+                (face_width + laps_width, face_height + date_height + self.below_face_height())
+            }
+            clocks => {
+                let label_width = self.world_clocks.iter().map(|(label, _)| label.chars().count() as u16 + 4).max().unwrap_or(0);
+                (face_width + label_width, face_height * clocks as u16)
+            }
+        };
+        width >= required_width && height >= required_height
+    }
+
+    /// Renders the user interface widgets.
+    pub fn render<B: Backend>(&mut self, frame: &mut Frame<'_, B>) {
+        // This is where you add new widgets.
+        // See the following resources:
+        // - https://docs.rs/tui/0.16.0/tui/widgets/index.html
+        // - https://github.com/fdehau/tui-rs/tree/v0.16.0/examples
+        self.fit_format(frame.size().width);
+        // Only filled in again if the face is drawn
+        self.animated_time.block_rects.clear();
+        if !self.check_size(frame.size().width, frame.size().height) {
+            let message = "Terminal too small — please resize";
+            let area = centered_rect(message.chars().count() as u16, 1, frame.size());
+            frame.render_widget(Paragraph::new(message).style(self.base_style()), area);
+            return;
+        }
+        self.render_gradient_bg(frame);
+        self.render_voronoi(frame);
+        self.render_slideshow(frame);
+        self.render_hypno(frame);
+        self.render_tab_bar(frame);
+        if self.config.circle_clock {
+            self.render_circle_clock(frame);
+        } else if self.config.table_mode {
+            self.render_table(frame);
+        } else if let Some(span) = self.config.progress_ring {
+            self.render_progress_ring(frame, span);
+        } else if !self.world_clocks.is_empty() {
+            self.render_world_clocks(frame);
+        } else if !self.laps.is_empty() {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(LAP_PANEL_WIDTH)])
+                .split(frame.size());
+            self.render_dated_face(frame, chunks[0]);
+            self.render_laps(frame, chunks[1]);
+        } else {
+            self.render_dated_face(frame, frame.size());
+        }
+
+        self.render_fireworks(frame);
+        self.render_confetti(frame);
+
+        if self.config.tick_indicator && self.tick_counter.is_multiple_of(2) {
+            let area = frame.size();
+            if area.width > 0 && area.height > 0 {
+                let corner = Rect::new(area.right() - 1, area.bottom() - 1, 1, 1);
+                frame.render_widget(Paragraph::new("*"), corner);
+            }
+        }
+
+        let is_flashing = self.flash_until.is_some_and(|until| Instant::now() < until);
+        if is_flashing || self.invert_until.is_some_and(|until| Instant::now() < until) {
+            frame.render_widget(Block::default().style(Style::default().add_modifier(Modifier::REVERSED)), frame.size());
+        }
+
+        #[cfg(feature = "mem-limit")]
+        self.render_memory_notice(frame);
+        self.render_precision(frame);
+        self.render_tooltip(frame);
+        self.render_pin_dialog(frame);
+
+        if self.color_depth != ColorDepth::TrueColor {
+            frame.render_widget(ColorDepthFilter(self.color_depth), frame.size());
+        }
+    }
+
+    /// Draws the current `--slideshow` image, if any, centred as a whole so its lines stay lined up.
+    fn render_slideshow<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let Some(slideshow) = &self.slideshow else { return };
+        let image = slideshow.current();
+        let width = image.lines().map(|line| line.chars().count()).max().unwrap_or(0) as u16;
+        let area = centered_rect(width, image.lines().count() as u16, frame.size());
+        frame.render_widget(Paragraph::new(image).style(self.base_style()), area);
+    }
+
+    /// Colours every cell of the terminal after its closest `--voronoi` seed, if enabled.
+    fn render_voronoi<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let Some(voronoi) = &self.voronoi else { return };
+        let area = frame.size();
+        let lines: Vec<Spans> = (0..area.height)
+            .map(|y| {
+                let spans: Vec<Span> = (0..area.width)
+                    .map(|x| {
+                        let style = voronoi.color_at(x, y, area.width, area.height).map_or(Style::default(), |color| Style::default().bg(color));
+                        Span::styled(" ", style)
+                    })
+                    .collect();
+                Spans::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// Draws the spinning `--hypno` rings, if any, centred on the terminal.
+    fn render_hypno<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        if self.hypno_phases.is_empty() {
+            return;
+        }
+        let area = frame.size();
+        if area.area() == 0 {
+            return;
+        }
+        // Terminal cells are about twice as tall as they are wide, so a cell is one unit wide and two tall
+        let (half_width, half_height) = (area.width as f64 / 2.0, area.height as f64);
+        let outer_radius = half_width.max(half_height);
+        let rings = self.hypno_phases.len() as f64;
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-half_width, half_width])
+            .y_bounds([-half_height, half_height])
+            .paint(|ctx| {
+                for (i, &phase) in self.hypno_phases.iter().enumerate() {
+                    shape::draw_ring(ctx, 0.0, 0.0, outer_radius * (i + 1) as f64 / rings, phase);
+                }
+            });
+        frame.render_widget(Transparent(canvas), area);
+    }
+
+    /// Fills the whole terminal with the `--gradient-bg`, if any.
+    fn render_gradient_bg<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let [from, to] = self.config.gradient_bg[..] else { return };
+        let area = frame.size();
+        // Shades of the second colour over the first, so the far edge is mostly the second colour
+        let frac = |i: u16, len: u16| i as f32 / len.saturating_sub(1).max(1) as f32;
+        let lines: Vec<Spans> = (0..area.height)
+            .map(|y| {
+                let row: String = (0..area.width)
+                    .map(|x| gradient_char(match self.config.gradient_bg_dir {
+                        GradientDirection::Horizontal => frac(x, area.width),
+                        GradientDirection::Vertical => frac(y, area.height),
+                    }))
+                    .collect();
+                Spans::from(row)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).style(Style::default().fg(to).bg(from)), area);
+    }
+
+    /// Renders the clock face within `area`, with the date above it if shown.
+    ///
+    /// The date takes a row of its own, pushing the face down.
+    fn render_dated_face<B: Backend>(&mut self, frame: &mut Frame<'_, B>, area: Rect) {
+        if !self.show_date {
+            self.render_face(frame, area);
+            return;
+        }
+        // Centring the face beneath the date row centres the date and face together
+        let face_area = Rect::new(area.x, area.y + DATE_HEIGHT.min(area.height), area.width, area.height.saturating_sub(DATE_HEIGHT));
+        let face_top = self.below_face(face_area, 0, 0).y;
+        // Dated in the same timezone as the clock, so the two roll over at midnight together
+        let date = match self.animated_time.timezone {
+            Some(tz) => format_token(&Utc::now().with_timezone(&tz), &self.date_format),
+            None => format_token(&Local::now(), &self.date_format)
+        };
+        let middle = Rect::new(area.x, face_top.saturating_sub(DATE_HEIGHT - DATE_HEIGHT / 2), area.width, 1).intersection(area);
+        let alignment = match self.align {
+            HorizontalAlign::Left => Alignment::Left,
+            HorizontalAlign::Center => Alignment::Center,
+            HorizontalAlign::Right => Alignment::Right
+        };
+        frame.render_widget(Paragraph::new(date).alignment(alignment).style(self.base_style()), middle);
+        self.render_face(frame, face_area);
+    }
+
+    /// Renders the digital clock face within `area`.
+    fn render_face<B: Backend>(&mut self, frame: &mut Frame<'_, B>, area: Rect) {
+        if self.is_narrow {
+            let middle = Rect::new(area.x, area.y + area.height / 2, area.width, area.height.min(1));
+            frame.render_widget(Paragraph::new(self.formatted_time()).alignment(Alignment::Center).style(self.base_style()), middle);
+            return;
+        }
+        let chunks = self.layout(area);
+        let blocks = self.animated_time.format_tokens.iter().map(|tokens| tokens.blocks.len()).sum();
+        self.animated_time.block_rects = chunks[..blocks].to_vec();
+        let base_style = self.base_style();
+        let sheet = self.style_sheet();
+        let border_type = self.digit_border_type().unwrap_or(BorderType::Rounded);
+        // `.transition { border: none }` wipes new tokens in without a box
+        let transition_box = sheet.transition.border_type().unwrap_or(Some(border_type)).map(|border_type| Block::default()
+                .borders(Borders::ALL)
+                .border_type(border_type)
+                .style(base_style));
+        let digit_box = Block::default()
+            .borders(Borders::ALL)
+            .border_type(match (self.is_border_flashed, border_type) {
+                // Flashing has to change the border, so double borders flash thick
+                (true, BorderType::Double) => BorderType::Thick,
+                (true, _) => BorderType::Double,
+                (false, border_type) => border_type
+            })
+            .style(base_style);
+        #[cfg(feature = "beat-sync")]
+        let digit_box = match self.beat_intensity() {
+            Some(intensity) => {
+                let level = 128 + (127.0 * intensity) as u8;
+                digit_box.border_style(Style::default().fg(Color::Rgb(level, level, level)).add_modifier(Modifier::BOLD))
+            }
+            None => digit_box,
+        };
+        if self.config.show_prev_time {
+            self.render_prev_time(frame, &chunks);
+        }
+        let columns = self.animated_time.format_tokens.iter().map(|tokens| tokens.blocks.len()).sum();
+        let mut i = 0;
+        for tokens in &self.animated_time.format_tokens {
+            for block in &tokens.blocks {
+                if self.config.hide_unchanged && !block.is_constant && self.animated_time.is_unchanged(block) {
+                    i += 1;
+                    continue;
+                }
+                let figure = self.font.figure(block.displayed_token());
+                // Colours of the clock from holidays, tabs and the config file win over `--fg` and
+                // the style sheet
+                let rule = match block.is_constant {
+                    true => sheet.constant,
+                    false => sheet.digit
+                };
+                let block_style = block.style.patch(rule.style()).patch(base_style);
+                let block_style = match self.color_mode.color(i, columns, self.render_elapsed) {
+                    Some(color) => block_style.fg(color),
+                    None => block_style
+                };
+                let style = match block.is_constant {
+                    true => block_style.patch(self.constant_style),
+                    false => block_style
+                };
+                // A blinked out separator leaves its block blank
+                let text = match block.blink_on {
+                    true => self.face_text(figure),
+                    false => String::new()
+                };
+                frame.render_widget(Paragraph::new(text).alignment(Alignment::Center).style(style), chunks[i]);
+                if !block.is_constant && self.is_bordered() {
+                    frame.render_widget(digit_box.clone().style(block_style), chunks[i]);
+                }
+                let progress = match self.config.no_animation {
+                    true => None,
+                    false => self.animated_time.transition_progress(block)
+                };
+                if let Some(progress) = progress {
+                    // Gravity always drops the new token in from the top
+                    let block_direction = if self.animated_time.is_gravity() {
+                        WipeDirection::TopDown
+                    } else if self.config.random_direction {
+                        block.direction
+                    } else if self.per_block_direction {
+                        // Neighbouring blocks wipe a quarter turn apart
+                        self.direction.turn((i % 2) as i8)
+                    } else {
+                        self.direction
+                    };
+                    let direction = block_direction.direction();
+                    // The new token grows over the leading chunk, or shrinks the old token into the
+                    // leading chunk when wiping in from the trailing edge
+                    let (constraint, chunk_index) = {
+                        let progress = match self.transition_curve {
+                            Some([p1x, p1y, p2x, p2y]) => cubic_bezier(p1x, p1y, p2x, p2y, progress as f32) as f64,
+                            None => progress
+                        };
+                        let constraint = (100.0 * progress).clamp(0.0, 100.0).round() as u16;
+                        if block_direction.is_trailing() {
+                            ([Constraint::Percentage(100 - constraint), Constraint::Percentage(constraint)], 1)
+                        } else {
+                            ([Constraint::Percentage(constraint), Constraint::Percentage(0)], 0)
+                        }
+                    };
+                    let block_area = chunks[i];
+                    let chunks = Layout::default()
+                        .direction(direction)
+                        .constraints(constraint)
+                        .split(block_area);
+                    frame.render_widget(Clear, chunks[chunk_index]);
+                    let figure = self.font.figure(&block.new_token);
+                    let transition_style = block_style.patch(block.transition_style).patch(sheet.transition.style());
+                    if let Some(transition_box) = transition_box.as_ref().filter(|_| self.is_bordered()) {
+                        frame.render_widget(transition_box.clone().style(transition_style), chunks[chunk_index]);
+                    }
+                    // A falling token shows its bottom edge first
+                    let scroll = match self.animated_time.is_gravity() {
+                        true => block_area.height.saturating_sub(chunks[chunk_index].height),
+                        false => 0
+                    };
+                    frame.render_widget(Paragraph::new(self.face_text(figure)).alignment(Alignment::Center).style(transition_style).scroll((scroll, 0)), chunks[chunk_index]);
+                }
+                self.render_particles(frame, block, chunks[i]);
+                i += 1
+            }
+        }
+        if self.config.show_timezone_offset {
+            // Looked up every frame so DST changes show up straight away
+            let text = format!("{}{}", "\n".repeat((self.face_height() / 2) as usize), timezone_offset(self.animated_time.timezone));
+            frame.render_widget(Paragraph::new(text).alignment(Alignment::Right).style(base_style), chunks[i]);
+        }
+        if let Some(label) = &self.config.border_label {
+            let face = self.below_face(area, 0, self.face_size().1);
+            let area = Rect::new(face.x.saturating_sub(1), face.y.saturating_sub(1), face.width + 2, face.height + 2)
+                .intersection(area);
+            let label_style = match self.config.border_label_color {
+                Some(color) => base_style.fg(color),
+                None => base_style
+            };
+            let outer_box = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(base_style)
+                .title(Span::styled(format!(" {} ", label), label_style));
+            frame.render_widget(outer_box, area);
+        }
+        let mut below = self.face_size().1;
+        if let Some(milliseconds) = &self.milliseconds {
+            let height = self.milliseconds_font.header_line.height as u16;
+            let area = self.below_face(area, below, height);
+            below += height;
+            let text = self.milliseconds_font.convert(&milliseconds.displayed()).map(|figure| figure.to_string()).unwrap_or_default();
+            frame.render_widget(Paragraph::new(text).alignment(Alignment::Center).style(base_style), area);
+        }
+        if self.config.progress_bar {
+            let area = self.below_face(area, below, PROGRESS_BAR_HEIGHT);
+            below += PROGRESS_BAR_HEIGHT;
+            // Leap seconds count past a billion nanoseconds
+            let ratio = (self.now().nanosecond() as f64 / 1e9).min(1.0);
+            // The bar is filled in its foreground colour, so it needs one to show up at all
+            let gauge_style = base_style.fg(self.config.progress_color.or(base_style.fg).unwrap_or(Color::Gray));
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).style(base_style))
+                .gauge_style(gauge_style)
+                .ratio(ratio)
+                .label("");
+            frame.render_widget(gauge, area);
+        }
+        if self.config.sparkline_seconds {
+            let area = self.below_face(area, below, SPARKLINE_HEIGHT);
+            below += SPARKLINE_HEIGHT;
+            let data: Vec<u64> = self.second_history.iter().copied().collect();
+            frame.render_widget(Sparkline::default().data(&data).max(59).style(base_style), area);
+        }
+        if self.config.barchart_seconds.is_some() {
+            let area = self.below_face(area, below, BARCHART_HEIGHT);
+            below += BARCHART_HEIGHT;
+            let data: Vec<(&str, u64)> = self.minute_seconds.iter().map(|(minute, seconds)| (minute.as_str(), *seconds)).collect();
+            frame.render_widget(BarChart::default().data(&data).max(60).bar_width(5).style(base_style), area);
+        }
+        if let Some((_, text)) = &self.sun_times {
+            let area = self.below_face(area, below, 1);
+            below += 1;
+            frame.render_widget(Paragraph::new(text.as_str()).alignment(Alignment::Center).style(base_style), area);
+        }
+        if let Some((_, (symbol, name))) = self.moon_phase {
+            let area = self.below_face(area, below, 1);
+            below += 1;
+            frame.render_widget(Paragraph::new(format!("{} {}", symbol, name)).alignment(Alignment::Center).style(base_style), area);
+        }
+        #[cfg(feature = "network")]
+        if let Some(weather) = &self.weather {
+            let area = self.below_face(area, below, 1);
+            below += 1;
+            // Left blank until the first report arrives
+            let text = weather.text().unwrap_or_default();
+            frame.render_widget(Paragraph::new(text).alignment(Alignment::Center).style(base_style), area);
+        }
+        #[cfg(feature = "network")]
+        if let Some(monitor) = &self.ntp_monitor {
+            let area = self.below_face(area, below, 1);
+            below += 1;
+            let (ratio, label) = match monitor.rtt() {
+                Some(rtt) => (rtt.as_secs_f64() / NTP_GAUGE_MAX.as_secs_f64(), format!("NTP {} ms", rtt.as_millis())),
+                None => (0.0, "NTP unreachable".to_string())
+            };
+            frame.render_widget(Gauge::default().ratio(ratio.min(1.0)).label(label).gauge_style(base_style.fg(base_style.fg.unwrap_or(Color::Gray))), area);
+        }
+        #[cfg(feature = "network")]
+        if let Some(ticker) = &self.news_ticker {
+            let area = self.below_face(area, below, 1);
+            below += 1;
+            frame.render_widget(Paragraph::new(ticker.text(area.width)).style(base_style), area);
+        }
+        if let Some(watch) = &self.watch {
+            let output_area = self.below_face(area, below, 1);
+            below += 1;
+            frame.render_widget(Paragraph::new(watch.output()).alignment(Alignment::Center).style(base_style), output_area);
+            if self.config.chart_history.is_some() {
+                self.render_chart(frame, self.below_face(area, below, CHART_HEIGHT), &watch.history());
+            }
+        }
+    }
+
+    /// Works out the phase of the moon again if it was last worked out over an hour before `now`.
+    fn update_moon_phase(&mut self, now: &DateTime<FixedOffset>) {
+        if self.moon_phase.is_some_and(|(updated, _)| *now - updated < chrono::Duration::hours(1)) {
+            return;
+        }
+        self.moon_phase = Some((*now, moon::moon_phase(moon::julian_day(now))));
+    }
+
+    /// Works out the sunrise and sunset at `(lat, lon)` again once `now` is a new day.
+    fn update_sun_times(&mut self, now: &DateTime<FixedOffset>, (lat, lon): (f64, f64)) {
+        let date = now.date_naive();
+        if self.sun_times.as_ref().is_some_and(|(computed, _)| *computed == date) {
+            return;
+        }
+        let local = |time: Option<NaiveTime>| match time {
+            Some(time) => Utc.from_utc_datetime(&date.and_time(time)).with_timezone(now.offset()).format("%H:%M").to_string(),
+            // The sun stays up, or down, all day
+            None => "--:--".to_string()
+        };
+        let text = format!("↑ {} ↓ {}", local(sun::sunrise(lat, lon, date)), local(sun::sunset(lat, lon, date)));
+        self.sun_times = Some((date, text));
+    }
+
+    /// Counts the second `now` falls in towards its minute, keeping the last `minutes` minutes.
+    fn count_second(&mut self, now: &DateTime<FixedOffset>, minutes: usize) {
+        if self.counted_second == Some(now.second()) {
+            return;
+        }
+        self.counted_second = Some(now.second());
+        let minute = now.format("%H:%M").to_string();
+        if self.minute_seconds.back().is_none_or(|(last, _)| *last != minute) {
+            if self.minute_seconds.len() == minutes {
+                self.minute_seconds.pop_front();
+            }
+            self.minute_seconds.push_back((minute, 0));
+        }
+        if let Some((_, seconds)) = self.minute_seconds.back_mut() {
+            *seconds += 1;
+        }
+    }
+
+    /// Height of the rows drawn below the clock face, such as `--milliseconds` and
+    /// `--progress-bar`, in terminal cells.
+    fn below_face_height(&self) -> u16 {
+        let milliseconds = match self.milliseconds {
+            Some(_) => self.milliseconds_font.header_line.height as u16,
+            None => 0
+        };
+        let watch = match (&self.watch, self.config.chart_history) {
+            (None, _) => 0,
+            (Some(_), None) => 1,
+            (Some(_), Some(_)) => 1 + CHART_HEIGHT
+        };
+        #[cfg(feature = "network")]
+        let network = [self.weather.is_some(), self.ntp_monitor.is_some(), self.news_ticker.is_some()].iter().filter(|&&shown| shown).count() as u16;
+        #[cfg(not(feature = "network"))]
+        let network = 0;
+        milliseconds
+            + if self.config.progress_bar { PROGRESS_BAR_HEIGHT } else { 0 }
+            + if self.config.sparkline_seconds { SPARKLINE_HEIGHT } else { 0 }
+            + if self.config.barchart_seconds.is_some() { BARCHART_HEIGHT } else { 0 }
+            + self.config.sunrise_sunset as u16
+            + self.config.moon_phase as u16
+            + network
+            + watch
+    }
+
+    /// Returns the area `height` cells tall, `offset` cells below the top of the clock face.
+    ///
+    /// The face is placed by `--align` and `--valign` together with the rows below it.
+    fn below_face(&self, size: Rect, offset: u16, height: u16) -> Rect {
+        let (width, face_height) = self.face_size();
+        let face = self.align(size, width, face_height + self.below_face_height());
+        let top = face.y + offset;
+        Rect::new(
+            face.x,
+            top.min(size.bottom()),
+            face.width,
+            height.min(size.bottom().saturating_sub(top)),
+        )
+    }
+
+    /// Returns the `width` by `height` area placed within `area` by `--align` and `--valign`,
+    /// shrunk to fit if needed.
+    fn align(&self, area: Rect, width: u16, height: u16) -> Rect {
+        let (width, height) = (width.min(area.width), height.min(area.height));
+        let x = match self.align {
+            HorizontalAlign::Left => 0,
+            HorizontalAlign::Center => (area.width - width) / 2,
+            HorizontalAlign::Right => area.width - width
+        };
+        let y = match self.valign {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Middle => (area.height - height) / 2,
+            VerticalAlign::Bottom => area.height - height
+        };
+        Rect::new(area.x + x, area.y + y, width, height)
+    }
+
+    /// Renders the `--chart-history` line chart of `data`, with the time axis ending at the latest entry.
+    fn render_chart<B: Backend>(&self, frame: &mut Frame<'_, B>, area: Rect, data: &[(f64, f64)]) {
+        let (Some(&(first, _)), Some(&(last, _))) = (data.first(), data.last()) else {
+            return;
+        };
+        let min = data.iter().map(|&(_, value)| value).fold(f64::INFINITY, f64::min);
+        let max = data.iter().map(|&(_, value)| value).fold(f64::NEG_INFINITY, f64::max);
+        // Keep a flat line off the edges of the chart
+        let (min, max) = if min == max { (min - 1.0, max + 1.0) } else { (min, max) };
+        let style = self.base_style();
+        let datasets = vec![Dataset::default().marker(Marker::Braille).graph_type(GraphType::Line).style(style).data(data)];
+        let chart = Chart::new(datasets)
+            .style(style)
+            .x_axis(Axis::default()
+                .bounds([first, last])
+                .labels(vec![Span::raw(format!("-{:.0}s", last - first)), Span::raw("now")]))
+            .y_axis(Axis::default()
+                .bounds([min, max])
+                .labels(vec![Span::raw(format!("{}", min)), Span::raw(format!("{}", max))]));
+        frame.render_widget(chart, area);
+    }
+
+    /// Renders each clock of the world clock in its own band of the terminal, with its label boxed on
+    /// the left.
+    fn render_world_clocks<B: Backend>(&mut self, frame: &mut Frame<'_, B>) {
+        let count = self.world_clocks.len() as u32;
+        let bands = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, count); count as usize])
+            .split(frame.size());
+        for (i, band) in bands.into_iter().enumerate() {
+            let label = self.world_clocks[i].0.clone();
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(label.chars().count() as u16 + 4), Constraint::Min(0)])
+                .split(band);
+            let label_box = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .style(self.base_style());
+            let label_area = Rect::new(chunks[0].x, chunks[0].y, chunks[0].width, chunks[0].height.min(3));
+            frame.render_widget(Paragraph::new(format!(" {}", label)).block(label_box), label_area);
+            // The face is drawn from `animated_time`, so each clock takes a turn in it
+            std::mem::swap(&mut self.animated_time, &mut self.world_clocks[i].1);
+            self.render_face(frame, chunks[1]);
+            std::mem::swap(&mut self.animated_time, &mut self.world_clocks[i].1);
+        }
+    }
+
+    /// Draws the sparks of any fireworks over the whole terminal.
+    fn render_fireworks<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let mut sparks = self.fireworks.sparks().peekable();
+        if sparks.peek().is_none() {
+            return;
+        }
+        // Canvases can't be drawn into a terminal with no cells
+        if frame.size().area() == 0 {
+            return;
+        }
+        let sparks: Vec<_> = sparks.collect();
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([0.0, fireworks::CANVAS_SIZE])
+            .y_bounds([0.0, fireworks::CANVAS_SIZE])
+            .paint(|ctx| {
+                for &(x, y, color) in &sparks {
+                    ctx.draw(&Points { coords: &[(x, y)], color });
+                }
+            });
+        frame.render_widget(Transparent(canvas), frame.size());
+    }
+
+    /// Draws each falling piece of confetti.
+    fn render_confetti<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let area = frame.size();
+        for piece in &self.confetti.pieces {
+            let x = area.x + (piece.x * area.width as f32) as u16;
+            let y = area.y + (piece.y * area.height as f32) as u16;
+            if x < area.right() && y < area.bottom() {
+                let tiny_rect = Rect::new(x, y, 1, 1);
+                frame.render_widget(Paragraph::new(piece.symbol.to_string()).style(Style::default().fg(piece.color)), tiny_rect);
+            }
+        }
+    }
+
+    /// Draws the particles left behind in a block, dimming them as they run out.
+    fn render_particles<B: Backend>(&self, frame: &mut Frame<'_, B>, block: &TokenBlock, area: Rect) {
+        let scale = self.scale() as i32;
+        // Figures are drawn centred, two lines down from the top of the block
+        let centre = (area.x + area.width / 2) as i32;
+        for particle in &block.particles {
+            let x = centre + particle.x as i32 * scale;
+            let y = area.y as i32 + (2 + particle.y as i32) * scale;
+            if !(area.y as i32..area.bottom() as i32).contains(&y) || !(area.x as i32..area.right() as i32).contains(&x) {
+                continue;
+            }
+            let style = match particle.lifetime < PARTICLE_DIM_LIFETIME {
+                true => self.base_style().add_modifier(Modifier::DIM),
+                false => self.base_style()
+            };
+            frame.render_widget(Paragraph::new(particle.symbol.to_string()).style(style), Rect::new(x as u16, y as u16, 1, 1));
+        }
+    }
+
+    /// Renders a dimmed ghost of the previous time, offset one cell down and right of each block.
+    fn render_prev_time<B: Backend>(&self, frame: &mut Frame<'_, B>, chunks: &[Rect]) {
+        let style = self.base_style().add_modifier(Modifier::DIM);
+        let size = frame.size();
+        let mut prev_chars = self.animated_time.prev_formatted.chars();
+        let blocks = self.animated_time.format_tokens.iter().flat_map(|tokens| &tokens.blocks);
+        for (block, chunk) in blocks.zip(chunks) {
+            let token: String = (&mut prev_chars).take(block.size).collect();
+            let Some(figure) = self.font.convert(&token) else {
+                continue;
+            };
+            let area = Rect::new(chunk.x + 1, chunk.y + 1, chunk.width, chunk.height).intersection(size);
+            frame.render_widget(Paragraph::new(self.face_text(figure)).alignment(Alignment::Center).style(style), area);
+        }
+    }
+
+    /// Renders the analogue clock face of `--circle-clock`, scaled to fit the terminal.
+    fn render_circle_clock<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let now = self.now();
+        let color = self.holiday_color.unwrap_or(Color::Reset);
+        let area = round_rect(frame.size());
+        if area.area() == 0 {
+            return;
+        }
+        let seconds = now.second() as f64 + now.nanosecond().min(999_999_999) as f64 / 1e9;
+        let minutes = now.minute() as f64 + seconds / 60.0;
+        let hours = (now.hour() % 12) as f64 + minutes / 60.0;
+        // Angles are measured clockwise from 12 o'clock
+        let hand = |turns: f64, length: f64, color: Color| {
+            let angle = std::f64::consts::TAU * turns;
+            Line { x1: 0.0, y1: 0.0, x2: length * angle.sin(), y2: length * angle.cos(), color }
+        };
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-1.0, 1.0])
+            .y_bounds([-1.0, 1.0])
+            .paint(|ctx| {
+                ctx.draw(&Circle { x: 0.0, y: 0.0, radius: 0.95, color });
+                for hour in 0..12 {
+                    let angle = std::f64::consts::TAU * hour as f64 / 12.0;
+                    let (x, y) = (angle.sin(), angle.cos());
+                    ctx.draw(&Line { x1: 0.85 * x, y1: 0.85 * y, x2: 0.95 * x, y2: 0.95 * y, color });
+                }
+                ctx.draw(&hand(hours / 12.0, 0.5, color));
+                ctx.draw(&hand(minutes / 60.0, 0.75, color));
+                ctx.draw(&hand(seconds / 60.0, 0.85, Color::Red));
+            });
+        frame.render_widget(canvas, area);
+
+        if self.config.digital_center {
+            let time = self.formatted_time();
+            let center = centered_rect(time.chars().count() as u16, 1, area);
+            frame.render_widget(Clear, center);
+            frame.render_widget(Paragraph::new(time).style(self.base_style()), center);
+        }
+    }
+
+    /// Renders the ring of `--progress-ring` around the time, scaled to fit the terminal.
+    fn render_progress_ring<B: Backend>(&self, frame: &mut Frame<'_, B>, span: RingSpan) {
+        let now = self.now();
+        let seconds = now.second() as f64 + now.nanosecond().min(999_999_999) as f64 / 1e9;
+        let progress = match span {
+            RingSpan::Minute => seconds / 60.0,
+            RingSpan::Hour => (now.minute() as f64 + seconds / 60.0) / 60.0
+        };
+        let area = round_rect(frame.size());
+        if area.area() == 0 {
+            return;
+        }
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-1.0, 1.0])
+            .y_bounds([-1.0, 1.0])
+            .paint(|ctx| shape::draw_arc(ctx, 0.0, 0.0, 0.9, 0.0, std::f64::consts::TAU * progress));
+        frame.render_widget(canvas, area);
+
+        let time = self.formatted_time();
+        frame.render_widget(Paragraph::new(time.clone()).style(self.base_style()), centered_rect(time.chars().count() as u16, 1, area));
+    }
+
+    /// Renders the panel of stopwatch laps, each with its own time and the total time.
+    fn render_laps<B: Backend>(&self, frame: &mut Frame<'_, B>, area: Rect) {
+        let format = |duration: Duration| {
+            let centiseconds = duration.as_millis() / 10;
+            format!("{:02}:{:02}.{:02}", centiseconds / 6000, centiseconds / 100 % 60, centiseconds % 100)
+        };
+        let lines: Vec<Spans> = self.laps
+            .iter()
+            .enumerate()
+            .map(|(i, &total)| {
+                let lap = total.saturating_sub(i.checked_sub(1).map_or(Duration::ZERO, |previous| self.laps[previous]));
+                Spans::from(format!("{:>3}  {}  {}", i + 1, format(lap), format(total)))
+            })
+            .collect();
+        let panel = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Laps ")
+            .style(self.base_style());
+        frame.render_widget(Paragraph::new(lines).block(panel).scroll((self.lap_scroll, 0)), area);
+    }
+
+    /// Renders the `--table-mode` table of the current and upcoming times.
+    fn render_table<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let format = self.animated_time.format_string();
+        // Formatted in the clock's own timezone so `%Z` shows its name rather than an offset
+        let times = match self.animated_time.timezone {
+            Some(tz) => table_times(&format, &Utc::now().with_timezone(&tz)),
+            None => table_times(&format, &Local::now())
+        };
+        let base_style = self.base_style();
+        let rows: Vec<Row> = TABLE_OFFSETS
+            .iter()
+            .zip(&times)
+            .map(|(&hours, time)| {
+                let label = match hours {
+                    0 => "now".to_string(),
+                    hours => format!("+{}h", hours)
+                };
+                let time = time.clone();
+                let style = match hours {
+                    0 => base_style.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                    _ => base_style
+                };
+                Row::new(vec![label, time]).style(style)
+            })
+            .collect();
+        let time_width = times.iter().map(|time| time.chars().count() as u16).max().unwrap_or(0);
+        let area = centered_rect(6 + 1 + time_width + 2, TABLE_OFFSETS.len() as u16 + 2, frame.size());
+        let widths = [Constraint::Length(6), Constraint::Length(time_width)];
+        let table = Table::new(rows)
+            .widths(&widths)
+            .style(base_style)
+            .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded));
+        frame.render_widget(table, area);
+    }
+
+    /// Renders the bar of `--tabs` preset names along the top, if any.
+    fn render_tab_bar<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        if self.presets.is_empty() {
+            return;
+        }
+        let size = frame.size();
+        let titles = self.presets.iter().map(|preset| Spans::from(preset.name.as_str())).collect();
+        let tabs = Tabs::new(titles)
+            .select(self.active_tab)
+            .style(self.base_style())
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(tabs, Rect::new(size.x, size.y, size.width, size.height.min(1)));
+    }
+
+    /// Renders the render tick statistics of `--precision-mode`.
+    fn render_precision<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        if !self.config.precision_mode {
+            return;
+        }
+        let target = self.render_tick_rate;
+        let measured = |value: Option<f64>, unit: &str| match value {
+            Some(value) => format!("{:.1} {}", value, unit),
+            None => "-".to_string()
+        };
+        let text = format!(
+            "target  {}\nactual  {}\nstd dev {}\njitter  {}",
+            measured((!target.is_zero()).then(|| 1.0 / target.as_secs_f64()), "Hz"),
+            measured(self.precision.average_rate(), "Hz"),
+            measured(self.precision.std_dev().map(|s| s * 1000.0), "ms"),
+            measured(self.precision.max_jitter(target).map(|s| s * 1000.0), "ms"),
+        );
+        let area = frame.size();
+        let area = Rect::new(area.x, area.y, 22.min(area.width), 6.min(area.height));
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(Block::default().title(" Precision ").borders(Borders::ALL).border_type(BorderType::Rounded)),
+            area,
+        );
+    }
+
+    /// Renders the `--tooltip-on-hover` of the block under the mouse, if any, just below and to
+    /// the right of the mouse.
+    fn render_tooltip<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        let Some((x, y)) = self.mouse_position.filter(|_| self.config.tooltip_on_hover) else { return };
+        let Some(index) = self.animated_time.block_at_screen_pos(x, y) else { return };
+        let Some((token, block)) = self.animated_time.format_tokens
+            .iter()
+            .flat_map(|token| token.blocks.iter().map(move |block| (token, block)))
+            .nth(index)
+        else {
+            return;
+        };
+        let lines = [
+            format!("token     {}", token.format_string),
+            format!("current   {}", block.curr_token),
+            format!("new       {}", block.new_token),
+            format!("progress  {}/{} ms", block.transition_progress, block.transition_timing),
+            format!("constant  {}", block.is_constant),
+        ];
+        let width = lines.iter().map(|line| line.chars().count() as u16).max().unwrap_or(0) + 2;
+        let height = lines.len() as u16 + 2;
+        let screen = frame.size();
+        // Flipped to the other side of the mouse where it would run off the screen
+        let left = match x + 1 + width > screen.right() {
+            true => x.saturating_sub(width),
+            false => x + 1
+        };
+        let top = match y + 1 + height > screen.bottom() {
+            true => y.saturating_sub(height),
+            false => y + 1
+        };
+        let area = Rect::new(left, top, width, height).intersection(screen);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines.join("\n"))
+                .style(self.base_style())
+                .block(Block::default().title(" Block ").borders(Borders::ALL).border_type(BorderType::Rounded)),
+            area,
+        );
+    }
+
+    /// Renders the PIN dialog of the lock screen, if open.
+    fn render_pin_dialog<B: Backend>(&self, frame: &mut Frame<'_, B>) {
+        if let Some((lock, entry)) = self.pin_lock.as_ref().and_then(|lock| Some((lock, lock.entry()?))) {
+            let mut text = "*".repeat(entry.len());
+            if lock.failed {
+                text.push_str("\nWrong PIN");
+            }
+            let area = centered_rect(30, 4, frame.size());
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(text)
+                    .alignment(Alignment::Center)
+                    .block(Block::default().title(" Enter PIN ").borders(Borders::ALL).border_type(BorderType::Rounded)),
+                area,
+            );
+        }
+    }
+}
+
+/// Label of a world clock showing `timezone`, the city of `America/New_York` as `New York`.
+fn timezone_label(timezone: Tz) -> String {
+    let name = timezone.name();
+    name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+}
+
+/// The current time in `timezone`, or the local timezone.
+fn now_in(timezone: Option<Tz>) -> DateTime<FixedOffset> {
+    match timezone {
+        Some(tz) => {
+            let now = Utc::now().with_timezone(&tz);
+            now.with_timezone(&now.offset().fix())
+        }
+        None => {
+            let now = Local::now();
+            now.with_timezone(now.offset())
+        }
+    }
+}
+
+/// Formats the current UTC offset of `timezone`, or the local timezone, as `+HH:MM`.
+fn timezone_offset(timezone: Option<Tz>) -> String {
+    let seconds = now_in(timezone).offset().local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Formats the sentinel dates that make a token come out widest and narrowest in `tz`, so block
+/// sizes can be worked out up front.
+fn format_extremes<Tz: TimeZone>(tz: &Tz, token: &str) -> (String, String)
+where
+    Tz::Offset: Display,
+{
+    let max_dt = tz.ymd(3000, 11, 11).and_hms_nano(12, 11, 11, 111111111);
+    let min_dt = tz.ymd(2222, 2, 2).and_hms_nano(1, 0, 0, 0);
+    (format_token(&max_dt, token), format_token(&min_dt, token))
+}
+
+/// Formats `dt` with a single token of a format string.
+///
+/// Adds `%2f` for centiseconds, which `strftime` lacks.
+fn format_token<Tz: TimeZone>(dt: &DateTime<Tz>, token: &str) -> String
+where
+    Tz::Offset: Display,
+{
+    match token {
+        "%2f" => dt.format("%3f").to_string().chars().take(2).collect(),
+        // Tokens chrono doesn't understand come out empty instead of panicking
+        _ => {
+            let mut formatted = String::new();
+            match write!(formatted, "{}", dt.format(token)) {
+                Ok(()) => formatted,
+                Err(_) => String::new()
+            }
+        }
+    }
+}
+
+/// Builds an [`AnimatedTime`] with the transition settings of `config`, drawn in `font`.
+fn animated_time(config: &AppConfig, font: &Font) -> AnimatedTime {
+    AnimatedTime::new()
+        .set_font(font.clone())
+        .set_timing(config.transition_timing)
+        .set_timing_per_token(&config.timing)
+        .set_easing(config.easing)
+        .set_stagger(config.stagger)
+        .set_blink_separator(config.blink_separator)
+        .set_style(color_style(config.fg, config.bg), color_style(config.transition_fg, None))
+        .set_random_direction(config.random_direction)
+        .set_adaptive_timing(config.adaptive_timing)
+        .set_spring(config.spring.then_some((config.spring_k, config.spring_damping)))
+        .set_gravity(config.gravity.then_some((config.gravity_strength, config.gravity_restitution)))
+        .set_slot_machine(config.slot_machine)
+        .set_typewriter(config.typewriter)
+        .set_no_animation(config.no_animation)
+        .set_particle_trail(config.particle_trail)
+        .set_timezone(config.timezone.first().copied())
+}
+
+/// `format` followed by ever narrower versions of it, first without seconds and then without
+/// minutes.
+///
+/// Composite specifiers such as the default `%X` are spelled out so their units can be dropped.
+fn format_cascade(format: &str) -> Vec<String> {
+    let mut cascade = vec![format.to_string()];
+    let mut wider = format.replace("%X", "%H:%M:%S").replace("%T", "%H:%M:%S").replace("%R", "%H:%M");
+    for unit in [":%S", ":%M"] {
+        let narrower = wider.replace(unit, "");
+        if narrower != wider && !narrower.is_empty() {
+            cascade.push(narrower.clone());
+        }
+        wider = narrower;
+    }
+    cascade
+}
+
+/// Style with foreground `fg` and background `bg`, where given.
+fn color_style(fg: Option<Color>, bg: Option<Color>) -> Style {
+    let style = fg.map_or(Style::default(), |fg| Style::default().fg(fg));
+    bg.map_or(style, |bg| style.bg(bg))
+}
+
+/// The times of the rows of the `--table-mode` table, `now` and the hours after it.
+fn table_times<Tz: TimeZone>(format: &str, now: &DateTime<Tz>) -> Vec<String>
+where
+    Tz::Offset: Display,
+{
+    TABLE_OFFSETS
+        .iter()
+        .map(|&hours| (now.clone() + chrono::Duration::hours(hours)).format(format).to_string())
+        .collect()
+}
+
+/// Returns the largest rectangle centered within `area` that looks square on screen.
+fn round_rect(area: Rect) -> Rect {
+    // Terminal cells are about twice as tall as they are wide
+    let width = area.width.min(area.height * 2);
+    centered_rect(width, width / 2, area)
+}
+
+/// Returns a `width` by `height` rectangle centered within `area`, shrunk to fit if needed.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
 }
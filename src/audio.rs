@@ -0,0 +1,92 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use rodio::{Decoder, OutputStream, Sink, Source};
+
+/// Requests sent to the playback thread.
+#[derive(Debug)]
+enum Command {
+    /// Play a file once.
+    Play(PathBuf),
+    /// Play a file over and over, replacing any loop already playing.
+    Loop(PathBuf),
+    /// Change the playback speed of the loop.
+    LoopSpeed(f32),
+    /// Stop the loop.
+    StopLoop,
+}
+
+/// Plays audio files on a background thread, so playback never blocks the clock.
+#[derive(Debug)]
+pub struct AudioPlayer {
+    /// Requests queued for the playback thread.
+    sender: mpsc::Sender<Command>,
+}
+
+impl AudioPlayer {
+    /// Starts the playback thread on the default audio output.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The output stream can't leave the thread it was opened on
+            let Ok((_stream, handle)) = OutputStream::try_default() else {
+                return;
+            };
+            let mut sound_loop: Option<Sink> = None;
+            // Failures are ignored, so a missing file never interrupts the clock
+            let decode = |path: &Path| File::open(path).ok().and_then(|file| Decoder::new(BufReader::new(file)).ok());
+            for command in receiver {
+                match command {
+                    Command::Play(path) => {
+                        if let Some(source) = decode(&path) {
+                            handle.play_raw(source.convert_samples()).ok();
+                        }
+                    }
+                    Command::Loop(path) => {
+                        sound_loop = decode(&path).and_then(|source| {
+                            let sink = Sink::try_new(&handle).ok()?;
+                            sink.append(source.repeat_infinite());
+                            Some(sink)
+                        });
+                    }
+                    Command::LoopSpeed(speed) => {
+                        if let Some(sink) = &sound_loop {
+                            sink.set_speed(speed);
+                        }
+                    }
+                    Command::StopLoop => sound_loop = None,
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    /// Starts playing the audio file at `path`, mixed over anything already playing.
+    pub fn play(&self, path: &Path) {
+        self.sender.send(Command::Play(path.to_path_buf())).ok();
+    }
+
+    /// Starts playing the audio file at `path` on repeat, replacing any loop already playing.
+    pub fn start_loop(&self, path: &Path) {
+        self.sender.send(Command::Loop(path.to_path_buf())).ok();
+    }
+
+    /// Sets the playback speed of the loop, where 1.0 is normal speed.
+    pub fn set_loop_speed(&self, speed: f32) {
+        self.sender.send(Command::LoopSpeed(speed)).ok();
+    }
+
+    /// Stops the loop.
+    pub fn stop_loop(&self) {
+        self.sender.send(Command::StopLoop).ok();
+    }
+}
+
+impl Default for AudioPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use crate::app::AppResult;
+use crate::event::Event;
+
+/// Number of samples per analysis hop.
+const HOP_SIZE: usize = 512;
+/// Seconds of onset history used for the tempo estimate.
+const HISTORY_SECS: f32 = 6.0;
+/// Slowest tempo considered.
+const MIN_BPM: f32 = 60.0;
+/// Fastest tempo considered.
+const MAX_BPM: f32 = 180.0;
+
+/// Beat detector listening on the default audio input device.
+#[derive(Debug)]
+pub struct BeatDetector {
+    /// Number of beats detected so far, notified on every beat.
+    pulse: Arc<(Mutex<u64>, Condvar)>,
+}
+
+impl BeatDetector {
+    /// Starts capturing audio and detecting beats in a background thread.
+    pub fn new() -> AppResult<Self> {
+        let pulse = Arc::new((Mutex::new(0), Condvar::new()));
+        let (ready_sender, ready_receiver) = mpsc::channel();
+        {
+            let pulse = pulse.clone();
+            thread::spawn(move || {
+                let (sender, receiver) = mpsc::channel();
+                // The stream has to stay alive (and on this thread) for as long as we listen.
+                let (_stream, sample_rate) = match open_input_stream(sender) {
+                    Ok(stream) => {
+                        ready_sender.send(Ok(())).expect("failed to report audio input state");
+                        stream
+                    }
+                    Err(e) => {
+                        ready_sender.send(Err(e.to_string())).expect("failed to report audio input state");
+                        return;
+                    }
+                };
+                let mut tracker = TempoTracker::new(sample_rate as f32 / HOP_SIZE as f32);
+                let mut hop = Vec::with_capacity(HOP_SIZE);
+                for samples in receiver {
+                    for sample in samples {
+                        hop.push(sample);
+                        if hop.len() == HOP_SIZE {
+                            if tracker.push(&hop) {
+                                let (count, cvar) = &*pulse;
+                                *count.lock().unwrap() += 1;
+                                cvar.notify_all();
+                            }
+                            hop.clear();
+                        }
+                    }
+                }
+            });
+        }
+        ready_receiver.recv()??;
+        Ok(Self { pulse })
+    }
+
+    /// Blocks until the next beat is detected.
+    pub fn wait_beat(&self) {
+        let (count, cvar) = &*self.pulse;
+        let count = count.lock().unwrap();
+        let current = *count;
+        drop(cvar.wait_while(count, |count| *count == current).unwrap());
+    }
+
+    /// Forwards every detected beat to the terminal event channel.
+    pub fn forward_to(self, sender: mpsc::Sender<Event>) {
+        thread::spawn(move || loop {
+            self.wait_beat();
+            if sender.send(Event::Beat).is_err() {
+                break;
+            }
+        });
+    }
+}
+
+/// Opens the default input device, sending mono sample chunks down `sender`.
+fn open_input_stream(sender: mpsc::Sender<Vec<f32>>) -> AppResult<(cpal::Stream, u32)> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or("no audio input device available")?;
+    let config = device.default_input_config()?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+    let sample_format = config.sample_format();
+    let config = config.into();
+    let stream = match sample_format {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                sender.send(downmix(data.iter().copied(), channels)).ok();
+            },
+            |_| {},
+            None,
+        )?,
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let data = data.iter().map(|&sample| sample as f32 / i16::MAX as f32);
+                sender.send(downmix(data, channels)).ok();
+            },
+            |_| {},
+            None,
+        )?,
+        format => return Err(format!("unsupported audio sample format {}", format).into()),
+    };
+    stream.play()?;
+    Ok((stream, sample_rate))
+}
+
+/// Averages interleaved samples down to a single channel.
+fn downmix(samples: impl Iterator<Item = f32>, channels: usize) -> Vec<f32> {
+    let samples: Vec<f32> = samples.collect();
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Running tempo estimate over an onset-strength envelope.
+#[derive(Debug)]
+struct TempoTracker {
+    /// Hops analysed per second.
+    hop_rate: f32,
+    prev_energy: f32,
+    onsets: VecDeque<f32>,
+    hops_since_beat: usize,
+}
+
+impl TempoTracker {
+    fn new(hop_rate: f32) -> Self {
+        Self { hop_rate, prev_energy: 0.0, onsets: VecDeque::new(), hops_since_beat: 0 }
+    }
+
+    /// Analyses one hop of samples, returning whether it lands on a beat.
+    fn push(&mut self, hop: &[f32]) -> bool {
+        let energy = hop.iter().map(|sample| sample * sample).sum::<f32>() / hop.len() as f32;
+        let onset = (energy - self.prev_energy).max(0.0);
+        self.prev_energy = energy;
+        self.onsets.push_back(onset);
+        if self.onsets.len() > (HISTORY_SECS * self.hop_rate) as usize {
+            self.onsets.pop_front();
+        }
+        self.hops_since_beat += 1;
+
+        let period = match self.period() {
+            Some(period) => period,
+            None => return false,
+        };
+        let count = self.onsets.len() as f32;
+        let mean = self.onsets.iter().sum::<f32>() / count;
+        let deviation = (self.onsets.iter().map(|o| (o - mean).powi(2)).sum::<f32>() / count).sqrt();
+        // Strong onsets re-align the phase, otherwise keep beating at the estimated tempo
+        let is_beat = (onset > mean + deviation && self.hops_since_beat * 2 > period)
+            || self.hops_since_beat >= period;
+        if is_beat {
+            self.hops_since_beat = 0;
+        }
+        is_beat
+    }
+
+    /// Beat period in hops, picked as the lag with the strongest autocorrelation.
+    fn period(&self) -> Option<usize> {
+        let min_lag = (self.hop_rate * 60.0 / MAX_BPM) as usize;
+        let max_lag = (self.hop_rate * 60.0 / MIN_BPM) as usize;
+        if min_lag == 0 || self.onsets.len() < 2 * max_lag {
+            return None;
+        }
+        (min_lag..=max_lag)
+            .map(|lag| {
+                let correlation = self.onsets.iter()
+                    .zip(self.onsets.iter().skip(lag))
+                    .map(|(a, b)| a * b)
+                    .sum::<f32>() / (self.onsets.len() - lag) as f32;
+                (lag, correlation)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, correlation)| *correlation > 0.0)
+            .map(|(lag, _)| lag)
+    }
+}
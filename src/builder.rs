@@ -0,0 +1,279 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono_tz::Tz;
+use tui::style::Color;
+
+use crate::config::{parse_pin, AnimationPreset, AppConfig, BorderStyle, CountdownTarget, GradientDirection, HorizontalAlign, RingSpan, StopwatchPrecision, TimingSpec, VerticalAlign};
+use crate::easing::EasingFn;
+
+/// A constraint between settings broken by an [`AppConfigBuilder`], as the command line would
+/// reject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Two settings that can't be used together were both set, named by their flags.
+    Conflict(&'static str, &'static str),
+    /// The first setting was set without the second, which it needs.
+    Requires(&'static str, &'static str),
+    /// A setting was given a value it can't take.
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict(a, b) => write!(f, "`--{}` can't be used with `--{}`", a, b),
+            Self::Requires(a, b) => write!(f, "`--{}` requires `--{}`", a, b),
+            Self::Invalid(flag, reason) => write!(f, "invalid `--{}`: {}", flag, reason)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Builds an [`AppConfig`] without going through the command line, starting from its defaults.
+///
+/// Each setting has a method of the same name, and [`build`] checks the settings go together
+/// the way the command line would.
+///
+/// [`build`]: AppConfigBuilder::build
+#[derive(Debug, Clone, Default)]
+pub struct AppConfigBuilder {
+    config: AppConfig,
+    /// Fields set so far, which the `animation_preset` leaves alone.
+    set: Vec<&'static str>,
+}
+
+/// Adds a method setting each field of the configuration.
+macro_rules! setters {
+    ($($(#[$cfg:meta])* $field:ident: $ty:ty,)*) => {
+        impl AppConfigBuilder {
+            $(
+                $(#[$cfg])*
+                #[doc = concat!("Sets [`AppConfig::", stringify!($field), "`].")]
+                pub fn $field(mut self, value: $ty) -> Self {
+                    self.config.$field = value;
+                    self.set.push(stringify!($field));
+                    self
+                }
+            )*
+        }
+    };
+}
+
+setters! {
+    config: Option<PathBuf>,
+    format: Option<String>,
+    milliseconds: bool,
+    date: bool,
+    date_format: String,
+    timezone: Vec<Tz>,
+    stdin_format: bool,
+    fg: Option<Color>,
+    bg: Option<Color>,
+    transition_fg: Option<Color>,
+    true_color: bool,
+    color_256: bool,
+    color_8: bool,
+    rainbow: bool,
+    rainbow_speed: f64,
+    font: Option<String>,
+    transition_timing: u128,
+    timing: Vec<TimingSpec>,
+    transition_curve: Option<[f32; 4]>,
+    animation_preset: Option<AnimationPreset>,
+    easing: EasingFn,
+    blink_separator: bool,
+    stagger: u128,
+    spring: bool,
+    spring_k: f32,
+    spring_damping: f32,
+    gravity: bool,
+    gravity_strength: f32,
+    gravity_restitution: f32,
+    slot_machine: bool,
+    typewriter: bool,
+    no_animation: bool,
+    particle_trail: bool,
+    adaptive_timing: bool,
+    show_prev_time: bool,
+    hide_unchanged: bool,
+    #[cfg(feature = "clipboard")]
+    clipboard: bool,
+    direction: Option<u8>,
+    dual_direction: bool,
+    random_direction: bool,
+    animate_constant: bool,
+    animate_constant_period: u128,
+    accessible: bool,
+    morse_audio: bool,
+    large_print: bool,
+    invert_colors_at_noon: bool,
+    invert_colors_at_midnight: bool,
+    flash_on_hour: bool,
+    fireworks: bool,
+    confetti: bool,
+    circle_clock: bool,
+    digital_center: bool,
+    table_mode: bool,
+    stopwatch: bool,
+    countdown: Option<CountdownTarget>,
+    #[cfg(feature = "audio")]
+    countdown_sound: Option<PathBuf>,
+    #[cfg(feature = "audio")]
+    countdown_warn_seconds: u64,
+    stopwatch_precision: StopwatchPrecision,
+    progress_ring: Option<RingSpan>,
+    gradient_bg: Vec<Color>,
+    hypno: Option<u64>,
+    slideshow: Option<PathBuf>,
+    slideshow_interval: u64,
+    voronoi: bool,
+    voronoi_seeds: u64,
+    gradient_bg_dir: GradientDirection,
+    smart_width: bool,
+    two_line: bool,
+    vertical: bool,
+    align: HorizontalAlign,
+    valign: VerticalAlign,
+    progress_bar: bool,
+    no_progress_bar: bool,
+    progress_color: Option<Color>,
+    sparkline_seconds: bool,
+    barchart_seconds: Option<u64>,
+    moon_phase: bool,
+    sunrise_sunset: bool,
+    location: Option<(f64, f64)>,
+    watch: Option<String>,
+    chart_history: Option<u32>,
+    #[cfg(feature = "audio")]
+    sound_on_hour: Option<PathBuf>,
+    show_timezone_offset: bool,
+    tabs: Option<PathBuf>,
+    holiday_colors: Option<PathBuf>,
+    style_file: Option<PathBuf>,
+    border_label: Option<String>,
+    border_label_color: Option<Color>,
+    no_border: bool,
+    border: BorderStyle,
+    compact: bool,
+    tick_indicator: bool,
+    precision_mode: bool,
+    tooltip_on_hover: bool,
+    overlay_mode: bool,
+    line: Option<u16>,
+    startup_delay: u64,
+    tick_rate: Option<u64>,
+    render_rate: Option<u64>,
+    frame_rate_limit: Option<u64>,
+    no_idle_throttle: bool,
+    no_clear: bool,
+    full_clear: bool,
+    restart_on_resize: bool,
+    kiosk: bool,
+    lock_screen: bool,
+    pin: Option<String>,
+    export_svg: Option<PathBuf>,
+    export_png: Option<PathBuf>,
+    benchmark: bool,
+    export_scale: u32,
+    #[cfg(feature = "network")]
+    news_ticker: Option<String>,
+    #[cfg(feature = "network")]
+    weather: Option<String>,
+    #[cfg(feature = "network")]
+    network_time_display: Option<String>,
+    #[cfg(feature = "mem-limit")]
+    memory_limit: Option<u64>,
+    #[cfg(feature = "debug")]
+    log_to_file: Option<PathBuf>,
+    #[cfg(feature = "debug")]
+    log_level: tracing::Level,
+    #[cfg(feature = "beat-sync")]
+    beat_sync: bool,
+}
+
+impl AppConfigBuilder {
+    /// Checks the settings go together and returns the configuration.
+    pub fn build(self) -> Result<AppConfig, ConfigError> {
+        let mut config = self.config;
+        let defaults = AppConfig::default();
+        let conflicts = [
+            ("true-color", config.true_color, "256-color", config.color_256),
+            ("true-color", config.true_color, "8-color", config.color_8),
+            ("256-color", config.color_256, "8-color", config.color_8),
+            ("easing", config.easing != EasingFn::Linear, "transition-curve", config.transition_curve.is_some()),
+            ("gravity", config.gravity, "spring", config.spring),
+            ("slot-machine", config.slot_machine, "spring", config.spring),
+            ("slot-machine", config.slot_machine, "gravity", config.gravity),
+            ("random-direction", config.random_direction, "dual-direction", config.dual_direction),
+            ("table-mode", config.table_mode, "circle-clock", config.circle_clock),
+            ("stopwatch", config.stopwatch, "tabs", config.tabs.is_some()),
+            ("countdown", config.countdown.is_some(), "tabs", config.tabs.is_some()),
+            ("countdown", config.countdown.is_some(), "stopwatch", config.stopwatch),
+            ("progress-ring", config.progress_ring.is_some(), "circle-clock", config.circle_clock),
+            ("full-clear", config.full_clear, "no-clear", config.no_clear),
+            ("vertical", config.vertical, "two-line", config.two_line),
+        ];
+        if let Some(&(a, _, b, _)) = conflicts.iter().find(|&&(_, is_a, _, is_b)| is_a && is_b) {
+            return Err(ConfigError::Conflict(a, b));
+        }
+        // Settings with defaults only need what they require once changed from the default
+        let requires = [
+            ("digital-center", config.digital_center, "circle-clock", config.circle_clock),
+            #[cfg(feature = "audio")]
+            ("countdown-sound", config.countdown_sound.is_some(), "countdown", config.countdown.is_some()),
+            ("slideshow-interval", config.slideshow_interval != defaults.slideshow_interval, "slideshow", config.slideshow.is_some()),
+            ("voronoi-seeds", config.voronoi_seeds != defaults.voronoi_seeds, "voronoi", config.voronoi),
+            ("gradient-bg-dir", config.gradient_bg_dir != defaults.gradient_bg_dir, "gradient-bg", !config.gradient_bg.is_empty()),
+            ("sunrise-sunset", config.sunrise_sunset, "location", config.location.is_some()),
+            ("chart-history", config.chart_history.is_some(), "watch", config.watch.is_some()),
+            ("border-label-color", config.border_label_color.is_some(), "border-label", config.border_label.is_some()),
+            ("line", config.line.is_some(), "overlay-mode", config.overlay_mode),
+            ("lock-screen", config.lock_screen, "pin", config.pin.is_some()),
+        ];
+        if let Some(&(a, _, b, _)) = requires.iter().find(|&&(_, is_a, _, is_b)| is_a && !is_b) {
+            return Err(ConfigError::Requires(a, b));
+        }
+        let at_least = |flag: &'static str, value: Option<u64>, min: u64| match value {
+            Some(value) if value < min => Err(ConfigError::Invalid(flag, format!("{} is less than {}", value, min))),
+            _ => Ok(())
+        };
+        at_least("hypno", config.hypno, 1)?;
+        at_least("slideshow-interval", Some(config.slideshow_interval), 1)?;
+        at_least("voronoi-seeds", Some(config.voronoi_seeds), 1)?;
+        at_least("barchart-seconds", config.barchart_seconds, 1)?;
+        at_least("chart-history", config.chart_history.map(u64::from), 2)?;
+        at_least("tick-rate", config.tick_rate, 1)?;
+        at_least("render-rate", config.render_rate, 1)?;
+        at_least("frame-rate-limit", config.frame_rate_limit, 1)?;
+        at_least("export-scale", Some(config.export_scale.into()), 1)?;
+        #[cfg(feature = "mem-limit")]
+        at_least("memory-limit", config.memory_limit, 1)?;
+        if let Some(hypno) = config.hypno.filter(|&hypno| hypno > 100) {
+            return Err(ConfigError::Invalid("hypno", format!("{} is more than 100", hypno)));
+        }
+        if let Some(render_rate) = config.render_rate.filter(|&render_rate| render_rate > 1000) {
+            return Err(ConfigError::Invalid("render-rate", format!("{} is more than 1000", render_rate)));
+        }
+        if let Some(direction) = config.direction.filter(|&direction| direction > 3) {
+            return Err(ConfigError::Invalid("direction", format!("{} is more than 3", direction)));
+        }
+        if !matches!(config.gradient_bg.len(), 0 | 2) {
+            return Err(ConfigError::Invalid("gradient-bg", "takes exactly two colours".to_string()));
+        }
+        if let Some(pin) = &config.pin {
+            parse_pin(pin).map_err(|e| ConfigError::Invalid("pin", e))?;
+        }
+        config.apply_animation_preset(|field| self.set.contains(&field));
+        Ok(config)
+    }
+}
+
+impl AppConfig {
+    /// Starts building a configuration from the defaults, without parsing a command line.
+    pub fn builder() -> AppConfigBuilder {
+        AppConfigBuilder::default()
+    }
+}
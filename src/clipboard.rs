@@ -0,0 +1,43 @@
+use std::fmt;
+
+use arboard::Clipboard;
+
+/// Copies the time to the system clipboard.
+#[derive(Default)]
+pub struct TimeClipboard {
+    /// Connection to the clipboard, opened on first use.
+    clipboard: Option<Clipboard>,
+    /// Text most recently copied.
+    last_copied: String,
+}
+
+impl fmt::Debug for TimeClipboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeClipboard")
+            .field("last_copied", &self.last_copied)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TimeClipboard {
+    /// Copies `text` unless it is already the last thing copied.
+    pub fn update(&mut self, text: &str) {
+        if text != self.last_copied {
+            self.copy(text);
+        }
+    }
+
+    /// Copies `text` to the clipboard.
+    ///
+    /// Failures are ignored, so a missing clipboard never interrupts the clock.
+    pub fn copy(&mut self, text: &str) {
+        if self.clipboard.is_none() {
+            self.clipboard = Clipboard::new().ok();
+        }
+        if let Some(clipboard) = &mut self.clipboard {
+            if clipboard.set_text(text).is_ok() {
+                self.last_copied = text.to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::env;
+use std::error;
+use std::fmt;
+
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Color;
+use tui::widgets::Widget;
+
+/// RGB values of the 16 named colours, as in xterm, in palette order.
+const NAMED_RGB: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+/// Channel levels of the 6x6x6 colour cube of the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Error returned when a colour spec can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorParseError(String);
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown colour `{}`", self.0)
+    }
+}
+
+impl error::Error for ColorParseError {}
+
+/// Parses a colour spec, such as `red`, `light-blue`, `#ff8800` or the 256-colour palette entry
+/// `ansi(208)`.
+pub fn parse_color(s: &str) -> Result<Color, ColorParseError> {
+    if let Some(hex) = s.trim().strip_prefix('#') {
+        return parse_hex(hex).ok_or_else(|| ColorParseError(s.to_string()));
+    }
+    if let Some(index) = s.trim().strip_prefix("ansi(").and_then(|rest| rest.strip_suffix(')')) {
+        return index.trim().parse().map(Color::Indexed).map_err(|_| ColorParseError(s.to_string()));
+    }
+    let name = s.trim().to_ascii_lowercase().replace(['-', '_', ' '], "");
+    Ok(match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return Err(ColorParseError(s.to_string())),
+    })
+}
+
+/// Parses the `RRGGBB` of a hex colour.
+fn parse_hex(hex: &str) -> Option<Color> {
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Number of colours a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The eight basic colours.
+    Ansi8,
+    /// The 256-colour palette.
+    Ansi256,
+    /// Any RGB colour.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Colour depth advertised by `$COLORTERM` and `$TERM`.
+    ///
+    /// Without either to go on, colours are left as they are.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || term.is_empty() {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi8
+        }
+    }
+}
+
+/// The colour closest to `c` that can be shown at `depth`.
+pub fn downgrade_color(c: Color, depth: ColorDepth) -> Color {
+    match (depth, c) {
+        (ColorDepth::TrueColor, _) | (_, Color::Reset) => c,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_indexed(r, g, b)),
+        (ColorDepth::Ansi256, _) => c,
+        (ColorDepth::Ansi8, Color::Rgb(..) | Color::Indexed(_)) => {
+            let rgb = to_rgb(c);
+            NAMED_RGB[..8]
+                .iter()
+                .min_by_key(|(_, named)| distance(*named, rgb))
+                .map_or(c, |(named, _)| *named)
+        }
+        // Bright colours drop to their normal counterparts
+        (ColorDepth::Ansi8, _) => {
+            let index = NAMED_RGB.iter().position(|(named, _)| *named == c).unwrap_or(0);
+            NAMED_RGB[index % 8].0
+        }
+    }
+}
+
+/// Approximate RGB values of `c`.
+fn to_rgb(c: Color) -> (u8, u8, u8) {
+    match c {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index @ 0..=15) => NAMED_RGB[index as usize].1,
+        Color::Indexed(index @ 16..=231) => {
+            let index = (index - 16) as usize;
+            (CUBE_LEVELS[index / 36], CUBE_LEVELS[index / 6 % 6], CUBE_LEVELS[index % 6])
+        }
+        Color::Indexed(index) => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+        _ => NAMED_RGB.iter().find(|(named, _)| *named == c).map_or((0, 0, 0), |(_, rgb)| *rgb),
+    }
+}
+
+/// Index of the colour closest to `(r, g, b)` in the colour cube or grey ramp of the 256-colour
+/// palette, leaving out the 16 named colours whose values vary between terminals.
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let grey = 232 + ((average.saturating_sub(3) / 10).min(23)) as u8;
+    match distance(to_rgb(Color::Indexed(grey)), (r, g, b)) < distance(to_rgb(Color::Indexed(cube)), (r, g, b)) {
+        true => grey,
+        false => cube,
+    }
+}
+
+/// Squared distance between two RGB colours.
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> i32 {
+    let (dr, dg, db) = (r1 as i32 - r2 as i32, g1 as i32 - g2 as i32, b1 as i32 - b2 as i32);
+    dr * dr + dg * dg + db * db
+}
+
+/// Widget that downgrades the colours already drawn in its area to a colour depth.
+pub struct ColorDepthFilter(pub ColorDepth);
+
+impl Widget for ColorDepthFilter {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                cell.fg = downgrade_color(cell.fg, self.0);
+                cell.bg = downgrade_color(cell.bg, self.0);
+            }
+        }
+    }
+}
+
+/// How the digits are coloured.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorMode {
+    /// In the colours they are given.
+    #[default]
+    Plain,
+    /// In hues spread across the columns, cycling through the spectrum `speed_hz` times a second.
+    Rainbow { speed_hz: f64 },
+}
+
+impl ColorMode {
+    /// Colour of column `column` of `columns` after `elapsed` seconds, if the mode colours it.
+    pub fn color(self, column: usize, columns: usize, elapsed: f64) -> Option<Color> {
+        match self {
+            ColorMode::Plain => None,
+            ColorMode::Rainbow { speed_hz } => {
+                let hue = (column as f64 / columns.max(1) as f64 + elapsed * speed_hz).rem_euclid(1.0);
+                let (r, g, b) = hsv_to_rgb(hue, 1.0, 1.0);
+                Some(Color::Rgb(r, g, b))
+            }
+        }
+    }
+}
+
+/// Converts a colour with hue `h`, saturation `s` and value `v`, each from 0.0 to 1.0, to RGB.
+pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let sector = (h.rem_euclid(1.0) * 6.0).floor();
+    let f = h.rem_euclid(1.0) * 6.0 - sector;
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    let (r, g, b) = match sector as u8 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    let channel = |c: f64| (c * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Shade character covering `frac` of a cell, from `░` at 0.0 through `▒` to `▓` at 1.0.
+pub fn gradient_char(frac: f32) -> char {
+    match frac {
+        f if f < 1.0 / 3.0 => '░',
+        f if f < 2.0 / 3.0 => '▒',
+        _ => '▓',
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tui::style::Color;
+
+/// Number of pieces thrown in each burst.
+const BURST_SIZE: usize = 50;
+/// Downward acceleration of each piece, in screen heights per second squared.
+const GRAVITY: f32 = 0.4;
+/// Characters a piece may be drawn as.
+const SYMBOLS: [char; 8] = ['*', '+', '#', '%', '@', '&', 'o', '~'];
+/// Colours a piece may be drawn in.
+const COLORS: [Color; 7] = [
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// A single piece of confetti.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Piece {
+    /// Horizontal position, from 0.0 (left edge) to 1.0 (right edge).
+    pub x: f32,
+    /// Vertical position, from 0.0 (top edge) to 1.0 (bottom edge).
+    pub y: f32,
+    /// Horizontal velocity, in screen widths per second.
+    vx: f32,
+    /// Vertical velocity, in screen heights per second.
+    vy: f32,
+    /// Character drawn for the piece.
+    pub symbol: char,
+    /// Colour the piece is drawn in.
+    pub color: Color,
+    /// Time left before the piece disappears.
+    lifetime: Duration,
+}
+
+/// Bursts of confetti falling down the screen.
+#[derive(Debug, Clone)]
+pub struct Confetti {
+    /// Pieces still falling.
+    pub pieces: Vec<Piece>,
+    /// When the last burst was thrown.
+    last_burst: Option<Instant>,
+    rng: StdRng,
+}
+
+impl Default for Confetti {
+    fn default() -> Self {
+        Self { pieces: Vec::new(), last_burst: None, rng: StdRng::from_entropy() }
+    }
+}
+
+impl Confetti {
+    /// Throws a burst of confetti from the top of the screen, at most once a second.
+    pub fn burst(&mut self) {
+        if self.last_burst.is_some_and(|last| last.elapsed() < Duration::from_secs(1)) {
+            return;
+        }
+        self.last_burst = Some(Instant::now());
+        for _ in 0..BURST_SIZE {
+            let piece = Piece {
+                x: self.rng.gen_range(0.0..1.0),
+                y: self.rng.gen_range(0.0..0.3),
+                vx: self.rng.gen_range(-0.1..0.1),
+                vy: self.rng.gen_range(0.0..0.3),
+                symbol: SYMBOLS[self.rng.gen_range(0..SYMBOLS.len())],
+                color: COLORS[self.rng.gen_range(0..COLORS.len())],
+                lifetime: Duration::from_millis(self.rng.gen_range(2000..5000)),
+            };
+            self.pieces.push(piece);
+        }
+    }
+
+    /// Moves every piece along by `dt`, clearing those that have expired or fallen off screen.
+    ///
+    /// Returns whether any pieces are left.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        let seconds = dt.as_secs_f32();
+        self.pieces.retain_mut(|piece| {
+            piece.vy += GRAVITY * seconds;
+            piece.x += piece.vx * seconds;
+            piece.y += piece.vy * seconds;
+            piece.lifetime = piece.lifetime.saturating_sub(dt);
+            !piece.lifetime.is_zero() && piece.y < 1.0 && (0.0..1.0).contains(&piece.x)
+        });
+        !self.pieces.is_empty()
+    }
+}
@@ -0,0 +1,861 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
+use serde::Deserialize;
+use tui::style::Color;
+use tui::widgets::BorderType;
+
+use crate::app::AppResult;
+use crate::color::{parse_color, ColorDepth};
+use crate::easing::EasingFn;
+
+/// Application configuration, parsed from the command line.
+#[derive(Debug, Clone, Parser)]
+#[command(author, version, about = "A simple flip clock for your terminal")]
+pub struct AppConfig {
+    /// TOML file of settings that are picked up while the clock is running.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// `strftime` format string of the time, e.g. `%H:%M` [default: %X].
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<String>,
+
+    /// Show the milliseconds in a small font below the time.
+    #[arg(long)]
+    pub milliseconds: bool,
+
+    /// Show the date above the time.
+    #[arg(long)]
+    pub date: bool,
+
+    /// `strftime` format string of the `--date`.
+    #[arg(long, value_name = "FORMAT", default_value_t = String::from("%A, %B %d %Y"))]
+    pub date_format: String,
+
+    /// IANA timezone to show the time in, e.g. `America/New_York` [default: local timezone].
+    ///
+    /// Repeat to show a world clock of every timezone stacked vertically, with `+` and `-` adding
+    /// and removing clocks.
+    #[arg(long, value_name = "TIMEZONE")]
+    pub timezone: Vec<Tz>,
+
+    /// Read format strings from stdin, one per line, switching to each as it arrives.
+    #[arg(long)]
+    pub stdin_format: bool,
+
+    /// Foreground colour of the digits, e.g. `#ff8800`.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub fg: Option<Color>,
+
+    /// Background colour of the digits, e.g. `#1a1a2e`.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub bg: Option<Color>,
+
+    /// Foreground colour of each new digit as it wipes in over the old one.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub transition_fg: Option<Color>,
+
+    /// Draw colours as they are, whatever `$COLORTERM` and `$TERM` say the terminal supports.
+    #[arg(long, conflicts_with_all = ["color_256", "color_8"])]
+    pub true_color: bool,
+
+    /// Draw colours from the 256-colour palette only, instead of detecting the colour depth.
+    #[arg(long = "256-color", conflicts_with = "color_8")]
+    pub color_256: bool,
+
+    /// Draw the eight basic colours only, instead of detecting the colour depth.
+    #[arg(long = "8-color")]
+    pub color_8: bool,
+
+    /// Colour the digits in a rainbow of hues drifting across the clock.
+    #[arg(long)]
+    pub rainbow: bool,
+
+    /// Times per second the `--rainbow` cycles through the spectrum.
+    #[arg(long, value_name = "HZ", default_value_t = 0.5)]
+    pub rainbow_speed: f64,
+
+    /// FIGfont to draw the clock in: `standard`, one of the bundled `small`, `slant` and `block`,
+    /// a font named in the FIGlet font directories, or the path to a `.flf` file.
+    #[arg(long, value_name = "NAME_OR_PATH")]
+    pub font: Option<String>,
+
+    /// Transition duration of each digit, in milliseconds.
+    #[arg(long, default_value_t = 250)]
+    pub transition_timing: u128,
+
+    /// Transition duration of the digits of each unit of time, in milliseconds, overriding
+    /// `--transition-timing`, e.g. `hours=300,minutes=200,seconds=100`.
+    #[arg(long, value_name = "UNIT=MS", value_delimiter = ',', value_parser = parse_timing_spec)]
+    pub timing: Vec<TimingSpec>,
+
+    /// Ease transitions along a CSS-style cubic Bézier curve, e.g. `0.4,0.0,0.2,1.0`.
+    #[arg(long, value_name = "BEZIER_POINTS", value_parser = parse_bezier)]
+    pub transition_curve: Option<[f32; 4]>,
+
+    /// Animate in one of a few bundled styles, overridden by any animation flag also given.
+    #[arg(long, value_enum, value_name = "NAME")]
+    pub animation_preset: Option<AnimationPreset>,
+
+    /// Ease transitions along a named curve.
+    #[arg(long, value_enum, default_value_t = EasingFn::Linear, conflicts_with = "transition_curve")]
+    pub easing: EasingFn,
+
+    /// Blink separators such as `:` on and off every half second.
+    #[arg(long)]
+    pub blink_separator: bool,
+
+    /// Start each digit's transition this many milliseconds after the digit before it, rippling
+    /// across the clock from left to right.
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    pub stagger: u128,
+
+    /// Drive transitions with spring physics instead of a fixed duration.
+    #[arg(long)]
+    pub spring: bool,
+
+    /// Stiffness of the `--spring` transition.
+    #[arg(long, value_name = "N", default_value_t = 170.0)]
+    pub spring_k: f32,
+
+    /// Damping of the `--spring` transition.
+    #[arg(long, value_name = "N", default_value_t = 26.0)]
+    pub spring_damping: f32,
+
+    /// Drop new digits in from the top of their block, bouncing as they land.
+    #[arg(long, conflicts_with = "spring")]
+    pub gravity: bool,
+
+    /// Acceleration of the `--gravity` transition, in block heights per second squared.
+    #[arg(long, value_name = "N", default_value_t = 16.0)]
+    pub gravity_strength: f32,
+
+    /// Fraction of speed kept on each bounce of the `--gravity` transition.
+    #[arg(long, value_name = "N", default_value_t = 0.4)]
+    pub gravity_restitution: f32,
+
+    /// Spin digits through the digits in between before landing, like a slot machine.
+    #[arg(long, conflicts_with_all = ["spring", "gravity"])]
+    pub slot_machine: bool,
+
+    /// Type out multi-character words such as weekday names one character at a time.
+    #[arg(long)]
+    pub typewriter: bool,
+
+    /// Show each new digit straight away, without any transition.
+    #[arg(long)]
+    pub no_animation: bool,
+
+    /// Leave a trail of decaying particles where each old digit was.
+    #[arg(long)]
+    pub particle_trail: bool,
+
+    /// Speed up digits that change often and slow down those that rarely do.
+    #[arg(long)]
+    pub adaptive_timing: bool,
+
+    /// Show a dimmed ghost of the previous time just behind the current one.
+    #[arg(long)]
+    pub show_prev_time: bool,
+
+    /// Blank digit blocks that haven't changed within the last second.
+    #[arg(long)]
+    pub hide_unchanged: bool,
+
+    /// Copy the time to the system clipboard whenever it changes.
+    #[cfg(feature = "clipboard")]
+    #[arg(long)]
+    pub clipboard: bool,
+
+    /// Edge new digits wipe in from: 0 top, 1 left, 2 bottom or 3 right [default: 0, or 1 with
+    /// --vertical].
+    #[arg(long, value_name = "0..3", value_parser = clap::value_parser!(u8).range(0..=3))]
+    pub direction: Option<u8>,
+
+    /// Alternate the transition direction between neighbouring digits.
+    #[arg(long)]
+    pub dual_direction: bool,
+
+    /// Pick a random direction for every digit transition.
+    #[arg(long, conflicts_with = "dual_direction")]
+    pub random_direction: bool,
+
+    /// Slowly pulse the brightness of constant blocks such as separators.
+    #[arg(long)]
+    pub animate_constant: bool,
+
+    /// Period of the `--animate-constant` brightness cycle, in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 2000)]
+    pub animate_constant_period: u128,
+
+    /// Write a plain-text description of the time to stdout whenever it changes, for screen readers.
+    ///
+    /// The clock is drawn on stderr, so stdout has to be redirected away from the terminal, e.g.
+    /// `clocktui --accessible | screen-reader-adapter`.
+    #[arg(long)]
+    pub accessible: bool,
+
+    /// Beep out the time in Morse code on the terminal bell every minute.
+    #[arg(long)]
+    pub morse_audio: bool,
+
+    /// Render digits at twice the size.
+    #[arg(long)]
+    pub large_print: bool,
+
+    /// Invert the colours for five seconds at noon.
+    #[arg(long)]
+    pub invert_colors_at_noon: bool,
+
+    /// Invert the colours for five seconds at midnight.
+    #[arg(long)]
+    pub invert_colors_at_midnight: bool,
+
+    /// Flash the clock for half a second at the top of each hour.
+    #[arg(long)]
+    pub flash_on_hour: bool,
+
+    /// Set off fireworks over the clock at the top of every hour.
+    #[arg(long)]
+    pub fireworks: bool,
+
+    /// Rain confetti down the screen at the start of every minute.
+    #[arg(long)]
+    pub confetti: bool,
+
+    /// Draw an analogue clock face instead of digits.
+    #[arg(long)]
+    pub circle_clock: bool,
+
+    /// Show the digital time in the centre of the `--circle-clock` face.
+    #[arg(long, requires = "circle_clock")]
+    pub digital_center: bool,
+
+    /// Show a table of the current time and the time 1, 2, 4, 8 and 24 hours from now.
+    #[arg(long, conflicts_with = "circle_clock")]
+    pub table_mode: bool,
+
+    /// Count up from when the clock started instead of showing the time of day.
+    ///
+    /// `Space` pauses and resumes, `r` resets, and `l` records a lap, scrolled with the arrow keys.
+    #[arg(long, conflicts_with = "tabs")]
+    pub stopwatch: bool,
+
+    /// Count down from a duration given as `MM:SS`, or to a local date and time given as
+    /// `YYYY-MM-DDTHH:MM:SS`, instead of showing the time of day.
+    #[arg(long, value_name = "MM:SS|DATETIME", value_parser = parse_countdown, conflicts_with_all = ["tabs", "stopwatch"])]
+    pub countdown: Option<CountdownTarget>,
+
+    /// Play this audio file on repeat, speeding up, through the last seconds of the `--countdown`.
+    #[cfg(feature = "audio")]
+    #[arg(long, value_name = "PATH", requires = "countdown")]
+    pub countdown_sound: Option<PathBuf>,
+
+    /// Number of seconds left on the `--countdown` when the `--countdown-sound` starts.
+    #[cfg(feature = "audio")]
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub countdown_warn_seconds: u64,
+
+    /// Smallest unit of time shown by the `--stopwatch`.
+    #[arg(long, value_name = "UNIT", value_enum, default_value_t = StopwatchPrecision::Cs)]
+    pub stopwatch_precision: StopwatchPrecision,
+
+    /// Draw a ring around the time that fills up over each minute, or each hour.
+    #[arg(long, value_name = "SPAN", num_args = 0..=1, default_missing_value = "minute", conflicts_with = "circle_clock")]
+    pub progress_ring: Option<RingSpan>,
+
+    /// Fill the background with a gradient between two colours, e.g. `blue magenta`.
+    #[arg(long, value_names = ["COLOR1", "COLOR2"], num_args = 2, value_parser = parse_color)]
+    pub gradient_bg: Vec<Color>,
+
+    /// Draw N concentric dashed rings spinning behind the clock, the innermost fastest, up to 100.
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "5", value_parser = clap::value_parser!(u64).range(1..=100))]
+    pub hypno: Option<u64>,
+
+    /// Show the ASCII art files in this directory behind the clock, in random order.
+    #[arg(long, value_name = "DIR")]
+    pub slideshow: Option<PathBuf>,
+
+    /// Number of seconds each `--slideshow` image is shown for.
+    #[arg(long, value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..), requires = "slideshow")]
+    pub slideshow_interval: u64,
+
+    /// Colour the background by a Voronoi diagram of seeds drifting around the terminal.
+    #[arg(long)]
+    pub voronoi: bool,
+
+    /// Number of seeds of the `--voronoi` background.
+    #[arg(long, value_name = "N", default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..), requires = "voronoi")]
+    pub voronoi_seeds: u64,
+
+    /// Direction of the `--gradient-bg`, from the first colour to the second.
+    #[arg(long, value_name = "DIRECTION", value_enum, default_value_t = GradientDirection::Horizontal, requires = "gradient_bg")]
+    pub gradient_bg_dir: GradientDirection,
+
+    /// Drop the seconds, then the minutes, when the time is too wide for the terminal, and show
+    /// it as plain text when even the hours don't fit.
+    #[arg(long)]
+    pub smart_width: bool,
+
+    /// Show the hours on one row and the rest of the time on a second row below it.
+    #[arg(long)]
+    pub two_line: bool,
+
+    /// Stack the blocks of the clock top to bottom, one per row, for tall and narrow terminals.
+    #[arg(long, conflicts_with = "two_line")]
+    pub vertical: bool,
+
+    /// Where the clock sits across the terminal.
+    #[arg(long, value_name = "ALIGN", value_enum, default_value_t = HorizontalAlign::Center)]
+    pub align: HorizontalAlign,
+
+    /// Where the clock sits down the terminal.
+    #[arg(long, value_name = "ALIGN", value_enum, default_value_t = VerticalAlign::Middle)]
+    pub valign: VerticalAlign,
+
+    /// Fill a bar below the clock over the course of each second.
+    #[arg(long, overrides_with = "no_progress_bar")]
+    pub progress_bar: bool,
+
+    /// Leave out the `--progress-bar`, as by default.
+    #[arg(long, overrides_with = "progress_bar")]
+    pub no_progress_bar: bool,
+
+    /// Colour of the filled part of the `--progress-bar`.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    pub progress_color: Option<Color>,
+
+    /// Chart the second of the minute over the last 60 logic ticks below the clock.
+    #[arg(long)]
+    pub sparkline_seconds: bool,
+
+    /// Chart how many seconds were shown in each of the last N minutes below the clock.
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10", value_parser = clap::value_parser!(u64).range(1..))]
+    pub barchart_seconds: Option<u64>,
+
+    /// Show the current phase of the moon below the clock.
+    #[arg(long)]
+    pub moon_phase: bool,
+
+    /// Show today's sunrise and sunset times at the `--location` below the clock.
+    #[arg(long, requires = "location")]
+    pub sunrise_sunset: bool,
+
+    /// Latitude and longitude in degrees, north and east positive, e.g. `51.5,-0.13`.
+    #[arg(long, value_name = "LAT,LON", value_parser = parse_location, allow_hyphen_values = true)]
+    pub location: Option<(f64, f64)>,
+
+    /// Run a shell command on every logic tick and show its latest output below the clock.
+    #[arg(long, value_name = "CMD")]
+    pub watch: Option<String>,
+
+    /// Plot the last N numbers printed by the `--watch` command as a line chart.
+    #[arg(long, value_name = "N", requires = "watch", value_parser = clap::value_parser!(u32).range(2..))]
+    pub chart_history: Option<u32>,
+
+    /// Play this audio file at the start of each hour.
+    #[cfg(feature = "audio")]
+    #[arg(long, value_name = "PATH")]
+    pub sound_on_hour: Option<PathBuf>,
+
+    /// Show the local UTC offset, such as `+05:30`, after the time.
+    #[arg(long)]
+    pub show_timezone_offset: bool,
+
+    /// TOML file of `[[preset]]` tables, each with a `name` and optional `format`, `color`,
+    /// `timezone` and `font`, switched between with the Tab key.
+    #[arg(long, value_name = "PRESETS_FILE")]
+    pub tabs: Option<PathBuf>,
+
+    /// TOML file mapping `"MM-DD"` dates to the colour of the clock on that day.
+    ///
+    /// The file is re-read whenever it changes.
+    #[arg(long, value_name = "CONFIG_FILE")]
+    pub holiday_colors: Option<PathBuf>,
+
+    /// CSS-like style sheet of `.digit`, `.constant` and `.transition` rules setting `color`,
+    /// `background` and `border`, e.g. `.digit { color: cyan; border: double; }`.
+    ///
+    /// The file is re-read whenever it changes.
+    #[arg(long, value_name = "PATH")]
+    pub style_file: Option<PathBuf>,
+
+    /// Draw a border around the whole clock with this label in its top-left corner.
+    #[arg(long, value_name = "TEXT")]
+    pub border_label: Option<String>,
+
+    /// Colour of the `--border-label` text, e.g. `yellow`.
+    #[arg(long, value_name = "COLOR", requires = "border_label", value_parser = parse_color)]
+    pub border_label_color: Option<Color>,
+
+    /// Don't draw borders around the digits.
+    #[arg(long)]
+    pub no_border: bool,
+
+    /// Borders of the boxes around digits, or `none` to leave the boxes out.
+    #[arg(long, value_name = "STYLE", value_enum, default_value_t = BorderStyle::Rounded)]
+    pub border: BorderStyle,
+
+    /// Draw the digits without boxes and the separators without spacing, narrowing the clock.
+    #[arg(long)]
+    pub compact: bool,
+
+    /// Blink a small indicator in the bottom-right corner on every logic tick.
+    #[arg(long)]
+    pub tick_indicator: bool,
+
+    /// Show the target and measured render tick rate, its standard deviation and max jitter.
+    #[arg(long)]
+    pub precision_mode: bool,
+
+    /// Show the format token, old and new tokens and transition progress of the digit block under
+    /// the mouse.
+    #[arg(long)]
+    pub tooltip_on_hover: bool,
+
+    /// Draw over the existing terminal content instead of switching to the alternate screen.
+    ///
+    /// The terminal can't be read back, so the lines drawn over are left blank on exit rather
+    /// than restored.
+    #[arg(long)]
+    pub overlay_mode: bool,
+
+    /// Draw the top of the clock at this line of the terminal in `--overlay-mode`, blanking the
+    /// lines it covers on exit.
+    #[arg(long, value_name = "N", requires = "overlay_mode")]
+    pub line: Option<u16>,
+
+    /// Wait this many milliseconds before taking over the terminal.
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    pub startup_delay: u64,
+
+    /// Milliseconds between logic ticks, which update the time and poll files for changes
+    /// [default: 200].
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub tick_rate: Option<u64>,
+
+    /// Milliseconds between frames while animating, from 1 to 1000 [default: 20, slower the more
+    /// digits are shown].
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..=1000))]
+    pub render_rate: Option<u64>,
+
+    /// Render at most this many frames per second [default: lower the more digits are shown].
+    #[arg(long, value_name = "FPS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub frame_rate_limit: Option<u64>,
+
+    /// Keep rendering at the full frame rate while nothing is animating, instead of once a second.
+    #[arg(long)]
+    pub no_idle_throttle: bool,
+
+    /// Never clear the screen, not even on startup, only redrawing the cells that change.
+    #[arg(long)]
+    pub no_clear: bool,
+
+    /// Clear the whole screen before every frame instead of redrawing only the cells that
+    /// change, for debugging flicker.
+    #[arg(long, conflicts_with = "no_clear")]
+    pub full_clear: bool,
+
+    /// Fully reinitialize the terminal whenever it is resized.
+    #[arg(long)]
+    pub restart_on_resize: bool,
+
+    /// Ignore all quit keys, exiting only on SIGTERM.
+    #[arg(long)]
+    pub kiosk: bool,
+
+    /// Ask for the PIN instead of exiting on the quit keys.
+    #[arg(long, requires = "pin")]
+    pub lock_screen: bool,
+
+    /// PIN that unlocks the lock screen.
+    #[arg(long, value_name = "DIGITS", value_parser = parse_pin)]
+    pub pin: Option<String>,
+
+    /// Export the current clock face as an SVG image to the given path and exit.
+    #[arg(long, value_name = "PATH")]
+    pub export_svg: Option<PathBuf>,
+
+    /// Export the current clock face as a PNG image to the given path and exit.
+    #[arg(long, value_name = "PATH")]
+    pub export_png: Option<PathBuf>,
+
+    /// Draw 1000 frames off screen, print how many frames per second were drawn and exit.
+    #[arg(long)]
+    pub benchmark: bool,
+
+    /// Size of each terminal cell in the exported PNG, as a multiple of 8x16 pixels.
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub export_scale: u32,
+
+    /// Scroll the headlines of the RSS feed at this URL below the clock, refreshed every five minutes.
+    #[cfg(feature = "network")]
+    #[arg(long, value_name = "RSS_URL")]
+    pub news_ticker: Option<String>,
+
+    /// Show the current temperature and conditions in this city below the clock, from wttr.in,
+    /// refreshed every ten minutes.
+    #[cfg(feature = "network")]
+    #[arg(long, value_name = "CITY")]
+    pub weather: Option<String>,
+
+    /// Show the round trip time to this NTP server as a gauge below the clock, measured every
+    /// second.
+    #[cfg(feature = "network")]
+    #[arg(long, value_name = "SERVER", num_args = 0..=1, default_missing_value = "pool.ntp.org")]
+    pub network_time_display: Option<String>,
+
+    /// Clear caches and trim histories, warning on stderr, whenever the heap grows past this many
+    /// megabytes.
+    #[cfg(feature = "mem-limit")]
+    #[arg(long, value_name = "MB", value_parser = clap::value_parser!(u64).range(1..))]
+    pub memory_limit: Option<u64>,
+
+    /// Write log output to daily files named after this path.
+    #[cfg(feature = "debug")]
+    #[arg(long, value_name = "PATH")]
+    pub log_to_file: Option<PathBuf>,
+
+    /// Least severe level of log output written by `--log-to-file`.
+    #[cfg(feature = "debug")]
+    #[arg(long, value_name = "LEVEL", default_value_t = tracing::Level::INFO)]
+    pub log_level: tracing::Level,
+
+    /// Flash the clock border in sync with the beat of the default audio input.
+    #[cfg(feature = "beat-sync")]
+    #[arg(long)]
+    pub beat_sync: bool,
+}
+
+/// Settings read from the `--config` file, which may change while the clock is running.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ConfigFile {
+    /// `strftime` format string of the time.
+    pub format: Option<String>,
+    /// Colour of the clock.
+    pub color: Option<String>,
+    /// Transition duration of each digit, in milliseconds.
+    pub transition_timing: Option<u64>,
+    /// FIGlet font the clock is drawn in, by name or path.
+    pub font: Option<String>,
+}
+
+impl ConfigFile {
+    /// Reads the settings from the TOML file at `path`.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Span of time a full `--progress-ring` represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RingSpan {
+    /// The ring fills up over each minute.
+    Minute,
+    /// The ring fills up over each hour.
+    Hour,
+}
+
+/// Direction of the `--gradient-bg`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GradientDirection {
+    /// From the left edge to the right edge.
+    Horizontal,
+    /// From the top edge to the bottom edge.
+    Vertical,
+}
+
+/// Delay between neighbouring digits of the `typewriter` animation preset, in milliseconds.
+const TYPEWRITER_STAGGER: u128 = 80;
+
+/// Bundle of animation settings, picked by `--animation-preset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AnimationPreset {
+    /// Slow eased transitions, all wiping the same way.
+    Gentle,
+    /// Quick linear transitions, each wiping in from a random edge.
+    Snappy,
+    /// Long spring transitions, wiping in from alternating edges.
+    Dramatic,
+    /// Digits revealed one after another from left to right, and words a character at a time.
+    Typewriter,
+}
+
+/// Borders of the boxes around digits, picked by `--border`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BorderStyle {
+    /// Lines with rounded corners.
+    Rounded,
+    /// Plain lines.
+    Single,
+    /// Double lines.
+    Double,
+    /// Heavy lines.
+    Thick,
+    /// No box at all.
+    None,
+}
+
+impl BorderStyle {
+    /// The border type drawn, or `None` if digits aren't boxed.
+    pub fn border_type(self) -> Option<BorderType> {
+        match self {
+            BorderStyle::Rounded => Some(BorderType::Rounded),
+            BorderStyle::Single => Some(BorderType::Plain),
+            BorderStyle::Double => Some(BorderType::Double),
+            BorderStyle::Thick => Some(BorderType::Thick),
+            BorderStyle::None => None
+        }
+    }
+}
+
+/// Where `--align` places the clock across the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HorizontalAlign {
+    /// Against the left edge.
+    Left,
+    /// Halfway between the edges.
+    Center,
+    /// Against the right edge.
+    Right,
+}
+
+/// Where `--valign` places the clock down the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VerticalAlign {
+    /// Against the top edge.
+    Top,
+    /// Halfway between the edges.
+    Middle,
+    /// Against the bottom edge.
+    Bottom,
+}
+
+/// When the `--countdown` ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountdownTarget {
+    /// After a duration from when the clock started.
+    In(Duration),
+    /// At a local date and time.
+    At(DateTime<Local>),
+}
+
+impl CountdownTarget {
+    /// Time left from now until the countdown ends, or zero if it already has.
+    pub fn remaining(&self) -> Duration {
+        match self {
+            CountdownTarget::In(duration) => *duration,
+            CountdownTarget::At(datetime) => (*datetime - Local::now()).to_std().unwrap_or(Duration::ZERO)
+        }
+    }
+}
+
+/// Unit of time that a `--timing` applies to the digits of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimeUnit {
+    /// Hours, in 24 or 12 hour time.
+    Hours,
+    /// Minutes past the hour.
+    Minutes,
+    /// Seconds past the minute.
+    Seconds,
+}
+
+impl TimeUnit {
+    /// How often the ones and tens digits of this unit change, in seconds.
+    fn periods(self) -> [i64; 2] {
+        match self {
+            TimeUnit::Hours => [3600, 36000],
+            TimeUnit::Minutes => [60, 600],
+            TimeUnit::Seconds => [1, 10],
+        }
+    }
+}
+
+/// Transition timing of the tokens showing one unit of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingSpec {
+    pub unit: TimeUnit,
+    /// Transition duration, in milliseconds.
+    pub timing: u128,
+}
+
+impl TimingSpec {
+    /// Whether a block changing every `period` seconds shows a digit of the unit of time, whichever
+    /// specifier it comes from, such as `%H`, `%-H` or the hours of `%X`.
+    pub fn matches(&self, period: i64) -> bool {
+        self.unit.periods().contains(&period)
+    }
+}
+
+/// Smallest unit of time shown by the `--stopwatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StopwatchPrecision {
+    /// Milliseconds, as `HH:MM:SS.sss`.
+    Ms,
+    /// Centiseconds, as `HH:MM:SS.ss`.
+    Cs,
+    /// Whole seconds, as `HH:MM:SS`.
+    S,
+}
+
+impl StopwatchPrecision {
+    /// Format string of the stopwatch time.
+    pub fn format(self) -> &'static str {
+        match self {
+            StopwatchPrecision::Ms => "%H:%M:%S.%3f",
+            StopwatchPrecision::Cs => "%H:%M:%S.%2f",
+            StopwatchPrecision::S => "%H:%M:%S",
+        }
+    }
+
+    /// Interval between render ticks keeping up with the running stopwatch, in milliseconds.
+    pub fn render_tick_rate(self) -> u64 {
+        match self {
+            StopwatchPrecision::Ms => 10,
+            StopwatchPrecision::Cs => 10,
+            StopwatchPrecision::S => 1000,
+        }
+    }
+}
+
+/// Parses the four comma separated control point coordinates of a cubic Bézier curve.
+fn parse_bezier(points: &str) -> Result<[f32; 4], String> {
+    let points = points
+        .split(',')
+        .map(|point| point.trim().parse::<f32>().map_err(|e| format!("invalid control point `{}`: {}", point, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let points: [f32; 4] = points
+        .try_into()
+        .map_err(|_| "expected four control point coordinates".to_string())?;
+    if !(0.0..=1.0).contains(&points[0]) || !(0.0..=1.0).contains(&points[2]) {
+        return Err("x coordinates must be between 0 and 1".to_string());
+    }
+    Ok(points)
+}
+
+/// Parses a latitude and longitude given in degrees as `LAT,LON`.
+fn parse_location(location: &str) -> Result<(f64, f64), String> {
+    let (lat, lon) = location
+        .split_once(',')
+        .and_then(|(lat, lon)| Some((lat.trim().parse::<f64>().ok()?, lon.trim().parse::<f64>().ok()?)))
+        .ok_or_else(|| format!("invalid location `{}`, expected LAT,LON", location))?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err("latitude must be between -90 and 90, and longitude between -180 and 180".to_string());
+    }
+    Ok((lat, lon))
+}
+
+/// Parses a countdown given as a duration of `MM:SS`, or a local date and time of `YYYY-MM-DDTHH:MM:SS`.
+fn parse_countdown(countdown: &str) -> Result<CountdownTarget, String> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(countdown, "%Y-%m-%dT%H:%M:%S") {
+        return Local
+            .from_local_datetime(&datetime)
+            .earliest()
+            .map(CountdownTarget::At)
+            .ok_or_else(|| format!("`{}` doesn't exist in the local timezone", countdown));
+    }
+    let (minutes, seconds) = countdown
+        .split_once(':')
+        .and_then(|(minutes, seconds)| Some((minutes.parse::<u64>().ok()?, seconds.parse::<u64>().ok()?)))
+        .filter(|&(_, seconds)| seconds < 60)
+        .ok_or_else(|| format!("invalid countdown `{}`, expected MM:SS or YYYY-MM-DDTHH:MM:SS", countdown))?;
+    Ok(CountdownTarget::In(Duration::from_secs(minutes * 60 + seconds)))
+}
+
+/// Parses a transition timing for a unit of time, given as `UNIT=MS`.
+fn parse_timing_spec(spec: &str) -> Result<TimingSpec, String> {
+    let (unit, timing) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("invalid timing `{}`, expected UNIT=MS", spec))?;
+    Ok(TimingSpec {
+        unit: TimeUnit::from_str(unit.trim(), true)?,
+        timing: timing.trim().parse().map_err(|e| format!("invalid timing `{}`: {}", timing, e))?,
+    })
+}
+
+/// Parses a PIN made up of digits only.
+pub(crate) fn parse_pin(pin: &str) -> Result<String, String> {
+    if !pin.is_empty() && pin.chars().all(|ch| ch.is_ascii_digit()) {
+        Ok(pin.to_string())
+    } else {
+        Err("PIN must be made up of digits".to_string())
+    }
+}
+
+impl AppConfig {
+    /// Colour depth to draw in, detected from the environment unless a flag picks one.
+    pub fn color_depth(&self) -> ColorDepth {
+        match (self.true_color, self.color_256, self.color_8) {
+            (true, _, _) => ColorDepth::TrueColor,
+            (_, true, _) => ColorDepth::Ansi256,
+            (_, _, true) => ColorDepth::Ansi8,
+            _ => ColorDepth::detect()
+        }
+    }
+
+    /// Parses `args` as the command line, then fills in the settings of the `--animation-preset`
+    /// that weren't passed on it.
+    pub fn parse_with_preset<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = Self::command().get_matches_from(args);
+        let mut config = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        config.apply_animation_preset(|id| matches.value_source(id) == Some(ValueSource::CommandLine));
+        config
+    }
+
+    /// Fills in the settings of the `--animation-preset`, leaving those `is_set` by their field
+    /// name as they are.
+    pub fn apply_animation_preset(&mut self, is_set: impl Fn(&str) -> bool) {
+        let Some(preset) = self.animation_preset else {
+            return;
+        };
+        let timing = match preset {
+            AnimationPreset::Gentle => Some(500),
+            AnimationPreset::Snappy => Some(100),
+            AnimationPreset::Dramatic => Some(1000),
+            AnimationPreset::Typewriter => None
+        };
+        if let Some(timing) = timing.filter(|_| !is_set("transition_timing")) {
+            self.transition_timing = timing;
+        }
+        // Switches only turn on where they don't clash with a setting already picked
+        match preset {
+            AnimationPreset::Gentle => {
+                if !is_set("easing") && self.transition_curve.is_none() {
+                    self.easing = EasingFn::EaseInOut;
+                }
+            }
+            AnimationPreset::Snappy => self.random_direction |= !self.dual_direction,
+            AnimationPreset::Dramatic => {
+                self.spring |= !self.gravity && !self.slot_machine;
+                self.dual_direction |= !self.random_direction;
+            }
+            AnimationPreset::Typewriter => {
+                self.typewriter = true;
+                if !is_set("stagger") {
+                    self.stagger = TYPEWRITER_STAGGER;
+                }
+                // Left to right, like the stagger
+                self.direction = self.direction.or(Some(1));
+            }
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::parse_from(["clocktui"])
+    }
+}
@@ -0,0 +1,80 @@
+use clap::ValueEnum;
+
+/// Curve mapping how far through its duration a transition is onto how far it has moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum EasingFn {
+    /// Moves at a constant speed.
+    #[default]
+    Linear,
+    /// Starts slow and speeds up.
+    EaseIn,
+    /// Starts fast and slows down.
+    EaseOut,
+    /// Starts and ends slow.
+    EaseInOut,
+    /// Starts and ends slow, more sharply than `ease-in-out`.
+    Cubic,
+}
+
+impl EasingFn {
+    /// Eases `t`, from 0.0 to 1.0.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EasingFn::Linear => t,
+            EasingFn::EaseIn => t * t,
+            EasingFn::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            EasingFn::EaseInOut if t < 0.5 => 2.0 * t * t,
+            EasingFn::EaseInOut => 1.0 - 2.0 * (1.0 - t) * (1.0 - t),
+            EasingFn::Cubic if t < 0.5 => 4.0 * t * t * t,
+            EasingFn::Cubic => 1.0 - 4.0 * (1.0 - t) * (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// Evaluates a CSS-style cubic Bézier easing curve at time `t`.
+///
+/// The curve runs from `(0, 0)` to `(1, 1)` through the control points
+/// `(p1x, p1y)` and `(p2x, p2y)`. The curve parameter matching `t` on the x axis
+/// is found with Newton's method, falling back to bisection where the slope is
+/// too flat to converge.
+pub fn cubic_bezier(p1x: f32, p1y: f32, p2x: f32, p2y: f32, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    let bezier = |p1: f32, p2: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+    let slope = |p1: f32, p2: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    let mut s = t;
+    for _ in 0..8 {
+        let error = bezier(p1x, p2x, s) - t;
+        if error.abs() < 1e-6 {
+            return bezier(p1y, p2y, s);
+        }
+        let derivative = slope(p1x, p2x, s);
+        if derivative.abs() < 1e-6 {
+            break;
+        }
+        s -= error / derivative;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = t;
+    for _ in 0..32 {
+        let x = bezier(p1x, p2x, s);
+        if (x - t).abs() < 1e-6 {
+            break;
+        }
+        if x < t {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    bezier(p1y, p2y, s)
+}
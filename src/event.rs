@@ -1,13 +1,31 @@
 use crate::app::AppResult;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
-use std::borrow::BorrowMut;
-use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use signal_hook::{consts::SIGTERM, iterator::Signals};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
 use std::sync::{mpsc, Arc, Mutex, Condvar};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// Interval between logic ticks, in milliseconds.
+pub const TICK_RATE: u64 = 200;
+
+/// Interval between render ticks while animating, in milliseconds.
+pub const RENDER_TICK_RATE: u64 = 20;
+
+/// Interval between render ticks while nothing is animating, in milliseconds.
+pub const IDLE_RENDER_TICK_RATE: u64 = 1000;
+
+/// Interval between render ticks while only blinking, in milliseconds.
+pub const BLINK_TICK_RATE: u64 = 500;
+
+/// Most render ticks sent in a second unless capped lower, one a millisecond.
+pub const MAX_FPS: u64 = 1000;
+
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Program Logic tick.
     LogicTick(Duration),
@@ -19,6 +37,43 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Termination requested by a signal.
+    Terminate,
+    /// New format string read from stdin.
+    Format(String),
+    /// Audio beat.
+    #[cfg(feature = "beat-sync")]
+    Beat,
+}
+
+/// Something that starts and stops the render ticks driving animations.
+pub trait AnimationTrigger {
+    /// Starts render ticks if `new_state` is true, or stops them once idle if false.
+    fn trigger_animation(&self, new_state: bool);
+
+    /// Changes the interval between render ticks while animating.
+    fn set_render_rate(&self, _render_tick_rate: Duration) {}
+
+    /// Keeps render ticks coming often enough to blink by, without going to the full rate, if
+    /// `new_state` is true.
+    fn trigger_blink(&self, _new_state: bool) {}
+}
+
+/// A source of events for the main loop, which also starts and stops the render ticks.
+pub trait Events: AnimationTrigger {
+    /// Receives the next event, blocking until there is one.
+    fn next(&self) -> AppResult<Event>;
+
+    /// Queues an event to be received after those already waiting.
+    fn send(&self, event: Event) -> AppResult<()>;
+}
+
+/// An [`AnimationTrigger`] that does nothing, for driving the application without rendering.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAnimationTrigger;
+
+impl AnimationTrigger for NoopAnimationTrigger {
+    fn trigger_animation(&self, _new_state: bool) {}
 }
 
 /// Terminal event handler.
@@ -28,20 +83,41 @@ pub struct EventHandler {
     sender: mpsc::Sender<Event>,
     /// Event receiver channel.
     receiver: mpsc::Receiver<Event>,
-    /// Event handler thread.
-    handlers: [thread::JoinHandle<()>; 2],
+    /// Event handler threads.
+    #[allow(dead_code)]
+    handlers: Vec<thread::JoinHandle<()>>,
+    /// State of the render thread, signalled whenever it changes.
+    render_state: Arc<(Mutex<RenderState>, Condvar)>,
+}
 
-    is_animating: Arc<(Mutex<bool>, Condvar)>,
+/// What the render thread ticks at.
+#[derive(Debug, Clone, Copy)]
+struct RenderState {
+    /// Whether anything is animating, so render ticks run at the full rate.
+    is_animating: bool,
+    /// Target interval between render ticks while animating.
+    render_tick_rate: Duration,
+    /// Whether something is blinking, so idle render ticks come at least as often as blinks.
+    is_blinking: bool,
+    /// Most render ticks sent in a second, however often they're asked for.
+    max_fps: u64,
 }
 
 impl EventHandler {
-    /// Constructs a new instance of [`EventHandler`].
+    /// Constructs a new instance of [`EventHandler`], rendering once a second while idle.
     pub fn new(tick_rate: u64, render_tick_rate: u64) -> Self {
+        Self::with_idle_render_rate(tick_rate, render_tick_rate, Some(IDLE_RENDER_TICK_RATE))
+    }
+
+    /// Constructs a new instance of [`EventHandler`] that renders every `idle_render_tick_rate`
+    /// milliseconds while nothing is animating, or at the full rate if `None`.
+    pub fn with_idle_render_rate(tick_rate: u64, render_tick_rate: u64, idle_render_tick_rate: Option<u64>) -> Self {
         let tick_rate = Duration::from_millis(tick_rate);
+        let idle_render_tick_rate = idle_render_tick_rate.map(Duration::from_millis);
         let render_tick_rate = Duration::from_millis(render_tick_rate);
         let (sender, receiver) = mpsc::channel();
-        let is_animating = Arc::new((Mutex::new(false), Condvar::new()));
-        let handlers = [
+        let render_state = Arc::new((Mutex::new(RenderState { is_animating: false, render_tick_rate, is_blinking: false, max_fps: MAX_FPS }), Condvar::new()));
+        let handlers = vec![
             {
                 let mut last_tick = Instant::now();
                 let sender = sender.clone();
@@ -72,19 +148,34 @@ impl EventHandler {
                 })
             },
             {
-                let is_animating = is_animating.clone();
+                let render_state = render_state.clone();
                 let sender = sender.clone();
-                let mut last_tick = Instant::now();
+                let blink_tick_rate = Duration::from_millis(BLINK_TICK_RATE);
                 thread::spawn(move || {
-                    let (is_animating, cvar) = &*is_animating;
+                    let (render_state, cvar) = &*render_state;
+                    let mut last_tick = Instant::now();
                     loop {
-                        drop(cvar.wait(is_animating.lock().unwrap()).unwrap());
-                        last_tick = Instant::now();
-                        while *is_animating.lock().unwrap() {
-                            if last_tick.elapsed() >= render_tick_rate {
-                                sender.send(Event::RenderTick(last_tick.elapsed())).expect("failed to send tick event");
-                                last_tick = Instant::now();
-                            }
+                        let state = render_state.lock().unwrap();
+                        let interval = match idle_render_tick_rate {
+                            Some(idle_rate) if !state.is_animating && state.is_blinking => idle_rate.min(blink_tick_rate),
+                            Some(idle_rate) if !state.is_animating => idle_rate,
+                            _ => state.render_tick_rate
+                        };
+                        // Ticks that would come sooner than `max_fps` allows are skipped
+                        let interval = interval.max(Duration::from_millis(1000 / state.max_fps.max(1)));
+                        let timeout = interval.saturating_sub(last_tick.elapsed());
+                        if state.is_animating || idle_render_tick_rate.is_none() {
+                            drop(state);
+                            thread::sleep(timeout);
+                        } else if cvar.wait_timeout(state, timeout).unwrap().0.is_animating {
+                            // A transition started, so go straight back to the full rate
+                            // without counting the idle time towards it
+                            last_tick = Instant::now();
+                            continue;
+                        }
+                        if last_tick.elapsed() >= interval {
+                            sender.send(Event::RenderTick(last_tick.elapsed())).expect("failed to send tick event");
+                            last_tick = Instant::now();
                         }
                     }
                 })
@@ -94,10 +185,30 @@ impl EventHandler {
             sender,
             receiver,
             handlers,
-            is_animating
+            render_state
         }
     }
 
+    /// Constructs an [`EventHandler`] without input or tick threads, fed only through [`send`].
+    ///
+    /// Useful for driving the application from tests.
+    ///
+    /// [`send`]: EventHandler::send
+    pub fn mock() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            handlers: Vec::new(),
+            render_state: Arc::new((Mutex::new(RenderState { is_animating: false, render_tick_rate: Duration::ZERO, is_blinking: false, max_fps: MAX_FPS }), Condvar::new()))
+        }
+    }
+
+    /// Queues an event as if it came from the terminal.
+    pub fn send(&self, event: Event) -> AppResult<()> {
+        Ok(self.sender.send(event)?)
+    }
+
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
@@ -106,12 +217,133 @@ impl EventHandler {
         Ok(self.receiver.recv()?)
     }
 
-    pub fn trigger_animation(&self, new_state: bool) {
-        let (is_animating, cvar) = &*self.is_animating;
-        let mut transitioning = is_animating.lock().unwrap();
-        if !*transitioning && new_state {
+    /// Caps render ticks at `max_fps` a second, skipping any that would come sooner.
+    pub fn set_max_fps(self, max_fps: u64) -> Self {
+        self.render_state.0.lock().unwrap().max_fps = max_fps;
+        self
+    }
+
+    /// Most render ticks sent in a second.
+    pub fn max_fps(&self) -> u64 {
+        self.render_state.0.lock().unwrap().max_fps
+    }
+
+    /// Target interval between render ticks.
+    pub fn render_tick_rate(&self) -> Duration {
+        self.render_state.0.lock().unwrap().render_tick_rate
+    }
+
+    /// Returns a sender for feeding events from other sources into the handler.
+    pub fn sender(&self) -> mpsc::Sender<Event> {
+        self.sender.clone()
+    }
+
+    /// Forwards `SIGTERM` as an [`Event::Terminate`] so the application can exit cleanly.
+    #[cfg(unix)]
+    pub fn forward_signals(&self) -> AppResult<()> {
+        let mut signals = Signals::new([SIGTERM])?;
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            for _ in signals.forever() {
+                if sender.send(Event::Terminate).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Forwards each line read from stdin as an [`Event::Format`], until stdin is closed.
+    pub fn forward_stdin_formats(&self) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            // Blank lines, as from `echo`, aren't worth switching to
+            for line in io::stdin().lock().lines().map_while(Result::ok).filter(|line| !line.is_empty()) {
+                if sender.send(Event::Format(line)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl Events for EventHandler {
+    fn next(&self) -> AppResult<Event> {
+        EventHandler::next(self)
+    }
+
+    fn send(&self, event: Event) -> AppResult<()> {
+        EventHandler::send(self, event)
+    }
+}
+
+impl AnimationTrigger for EventHandler {
+    fn trigger_animation(&self, new_state: bool) {
+        let (render_state, cvar) = &*self.render_state;
+        let mut state = render_state.lock().unwrap();
+        if !state.is_animating && new_state {
+            cvar.notify_one();
+        }
+        state.is_animating = new_state;
+    }
+
+    fn set_render_rate(&self, render_tick_rate: Duration) {
+        let (render_state, cvar) = &*self.render_state;
+        let mut state = render_state.lock().unwrap();
+        if state.render_tick_rate != render_tick_rate {
+            state.render_tick_rate = render_tick_rate;
             cvar.notify_one();
         }
-        *transitioning = new_state;
+    }
+
+    fn trigger_blink(&self, new_state: bool) {
+        let (render_state, cvar) = &*self.render_state;
+        let mut state = render_state.lock().unwrap();
+        if state.is_blinking != new_state {
+            state.is_blinking = new_state;
+            // Wakes the idle wait so the next tick comes at the blink rate
+            cvar.notify_one();
+        }
+    }
+}
+
+/// [`Events`] read from a queue filled in advance, without any threads.
+///
+/// Useful for driving the application from tests.
+#[derive(Debug, Default)]
+pub struct MockEvents {
+    /// Events still to be received, oldest first.
+    events: RefCell<VecDeque<Event>>,
+    /// Whether render ticks were last asked to run at the full rate.
+    is_animating: Cell<bool>,
+}
+
+impl MockEvents {
+    /// Constructs a queue of `events`, received in order.
+    pub fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Self { events: RefCell::new(events.into_iter().collect()), is_animating: Cell::new(false) }
+    }
+
+    /// Whether render ticks were last asked to run at the full rate.
+    pub fn is_animating(&self) -> bool {
+        self.is_animating.get()
+    }
+}
+
+impl Events for MockEvents {
+    fn next(&self) -> AppResult<Event> {
+        // Nothing else could ever queue an event, so waiting would block forever
+        self.events.borrow_mut().pop_front().ok_or_else(|| "no more events queued".into())
+    }
+
+    fn send(&self, event: Event) -> AppResult<()> {
+        self.events.borrow_mut().push_back(event);
+        Ok(())
+    }
+}
+
+impl AnimationTrigger for MockEvents {
+    fn trigger_animation(&self, new_state: bool) {
+        self.is_animating.set(new_state);
     }
 }
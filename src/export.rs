@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use font8x8::{UnicodeFonts, BASIC_FONTS, BLOCK_FONTS, BOX_FONTS};
+use image::{Rgb, RgbImage};
+use tui::buffer::Buffer;
+use tui::style::Color;
+
+/// Width of a terminal cell in the exported image, in pixels.
+const CELL_WIDTH: u32 = 10;
+/// Height of a terminal cell in the exported image, in pixels.
+const CELL_HEIGHT: u32 = 20;
+/// Width of a bitmap font glyph, in pixels.
+const GLYPH_WIDTH: u32 = 8;
+/// Height of a bitmap font glyph once stretched to terminal cell proportions, in pixels.
+const GLYPH_HEIGHT: u32 = 16;
+/// Height of the clock face, in terminal cells.
+pub const FACE_HEIGHT: u16 = 9;
+
+/// A single block of the clock face, as laid out on the terminal.
+#[derive(Debug, Clone)]
+pub struct ExportBlock {
+    /// Text drawn in the block, as laid out on the terminal.
+    pub figure: String,
+    /// Width of the block, in terminal cells.
+    pub width: u16,
+    /// Whether a border is drawn around the block.
+    pub bordered: bool,
+}
+
+/// Renders the clock face as a standalone SVG document.
+///
+/// `face_height` is the height of the clock face, in terminal cells.
+pub fn svg(blocks: &[ExportBlock], face_height: u16) -> String {
+    let width = blocks.iter().map(|block| block.width as u32).sum::<u32>() * CELL_WIDTH;
+    let height = face_height as u32 * CELL_HEIGHT;
+    let mut svg = String::new();
+    writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" xml:space="preserve">"#).unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="black"/>"#).unwrap();
+    writeln!(svg, r#"<g fill="white" font-family="monospace" font-size="{}">"#, CELL_HEIGHT * 4 / 5).unwrap();
+    let mut column = 0;
+    for block in blocks {
+        let x = column * CELL_WIDTH;
+        if block.bordered {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="none" stroke="white"/>"#,
+                x + CELL_WIDTH / 2,
+                CELL_HEIGHT / 2,
+                (block.width as u32 - 1) * CELL_WIDTH,
+                (face_height as u32 - 1) * CELL_HEIGHT,
+                CELL_WIDTH,
+            ).unwrap();
+        }
+        // Mirrors the centering used when rendering to the terminal
+        for (row, line) in block.figure.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let offset = (block.width as usize).saturating_sub(line.chars().count()) / 2;
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" textLength="{}">{}</text>"#,
+                x + offset as u32 * CELL_WIDTH,
+                (row as u32 + 1) * CELL_HEIGHT - CELL_HEIGHT / 4,
+                line.chars().count() as u32 * CELL_WIDTH,
+                escape(line),
+            ).unwrap();
+        }
+        column += block.width as u32;
+    }
+    writeln!(svg, "</g>").unwrap();
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+/// Escapes text for use inside an XML element.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Rasterises a terminal buffer using the bundled bitmap font, scaling each cell by `scale`.
+pub fn png(buffer: &Buffer, scale: u32) -> RgbImage {
+    let area = buffer.area();
+    let (cell_width, cell_height) = (GLYPH_WIDTH * scale, GLYPH_HEIGHT * scale);
+    let mut image = RgbImage::new(area.width as u32 * cell_width, area.height as u32 * cell_height);
+    for y in 0..area.height {
+        for x in 0..area.width {
+            let cell = buffer.get(area.x + x, area.y + y);
+            let foreground = rgb(cell.fg, [255, 255, 255]);
+            let background = rgb(cell.bg, [0, 0, 0]);
+            let glyph = cell.symbol.chars().next()
+                .and_then(|ch| BASIC_FONTS.get(ch).or_else(|| BOX_FONTS.get(ch)).or_else(|| BLOCK_FONTS.get(ch)))
+                .unwrap_or([0; 8]);
+            for py in 0..cell_height {
+                // Glyph rows are doubled to match the proportions of a terminal cell
+                let row = glyph[(py * 8 / cell_height) as usize];
+                for px in 0..cell_width {
+                    let is_set = row & (1 << (px * GLYPH_WIDTH / cell_width)) != 0;
+                    image.put_pixel(
+                        x as u32 * cell_width + px,
+                        y as u32 * cell_height + py,
+                        if is_set { foreground } else { background },
+                    );
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Resolves a terminal colour to RGB, using `default` for the terminal's own colour.
+fn rgb(color: Color, default: [u8; 3]) -> Rgb<u8> {
+    Rgb(match color {
+        Color::Reset => default,
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::Gray => [229, 229, 229],
+        Color::DarkGray => [127, 127, 127],
+        Color::LightRed => [255, 0, 0],
+        Color::LightGreen => [0, 255, 0],
+        Color::LightYellow => [255, 255, 0],
+        Color::LightBlue => [92, 92, 255],
+        Color::LightMagenta => [255, 0, 255],
+        Color::LightCyan => [0, 255, 255],
+        Color::White => [255, 255, 255],
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Indexed(_) => default,
+    })
+}
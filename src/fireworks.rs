@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tui::style::Color;
+
+/// How long each burst lasts, including the fade.
+const BURST_DURATION: Duration = Duration::from_secs(3);
+/// How long before the end of a burst its sparks start to fade.
+const FADE_DURATION: Duration = Duration::from_secs(1);
+/// Number of bursts launched at once.
+const BURSTS: usize = 3;
+/// Number of sparks in each burst.
+const SPARKS: usize = 40;
+/// Downward acceleration of each spark, in canvas units per second squared.
+const GRAVITY: f64 = 20.0;
+/// Colours a spark may be drawn in.
+const COLORS: [Color; 6] = [
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+];
+
+/// Width and height of the canvas the fireworks are drawn on, in canvas units.
+pub const CANVAS_SIZE: f64 = 100.0;
+
+/// A spark thrown out from the centre of a burst.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spark {
+    /// Horizontal velocity, in canvas units per second.
+    vx: f64,
+    /// Initial upward velocity, in canvas units per second.
+    vy: f64,
+    /// Colour the spark is drawn in until it fades.
+    color: Color,
+}
+
+/// Sparks flying out from a single point.
+#[derive(Debug, Clone, PartialEq)]
+struct Burst {
+    /// Horizontal position of the centre, in canvas units.
+    x: f64,
+    /// Vertical position of the centre, in canvas units from the bottom.
+    y: f64,
+    /// When the burst went off.
+    launched: Instant,
+    sparks: Vec<Spark>,
+}
+
+/// Fireworks bursting over the clock.
+#[derive(Debug, Clone)]
+pub struct Fireworks {
+    bursts: Vec<Burst>,
+    /// When the last bursts were launched.
+    last_launch: Option<Instant>,
+    rng: StdRng,
+}
+
+impl Default for Fireworks {
+    fn default() -> Self {
+        Self { bursts: Vec::new(), last_launch: None, rng: StdRng::from_entropy() }
+    }
+}
+
+impl Fireworks {
+    /// Sets off a few bursts across the upper half of the canvas, at most once a second.
+    pub fn launch(&mut self) {
+        if self.last_launch.is_some_and(|last| last.elapsed() < Duration::from_secs(1)) {
+            return;
+        }
+        let launched = Instant::now();
+        self.last_launch = Some(launched);
+        for _ in 0..BURSTS {
+            let sparks = (0..SPARKS)
+                .map(|_| {
+                    let angle = self.rng.gen_range(0.0..std::f64::consts::TAU);
+                    let speed = self.rng.gen_range(10.0..30.0);
+                    Spark { vx: speed * angle.cos(), vy: speed * angle.sin(), color: COLORS[self.rng.gen_range(0..COLORS.len())] }
+                })
+                .collect();
+            self.bursts.push(Burst {
+                x: self.rng.gen_range(20.0..80.0),
+                y: self.rng.gen_range(50.0..80.0),
+                launched,
+                sparks,
+            });
+        }
+    }
+
+    /// Clears bursts that have burnt out, returning whether any are left.
+    pub fn tick(&mut self) -> bool {
+        self.bursts.retain(|burst| burst.launched.elapsed() < BURST_DURATION);
+        !self.bursts.is_empty()
+    }
+
+    /// Current position and colour of every spark, following `y = v₀t - ½gt²` from the centre of
+    /// its burst.
+    ///
+    /// Sparks turn grey as their burst fades out.
+    pub fn sparks(&self) -> impl Iterator<Item = (f64, f64, Color)> + '_ {
+        self.bursts.iter().flat_map(|burst| {
+            let elapsed = burst.launched.elapsed();
+            let t = elapsed.as_secs_f64();
+            let is_fading = elapsed + FADE_DURATION >= BURST_DURATION;
+            burst.sparks.iter().map(move |spark| {
+                let x = burst.x + spark.vx * t;
+                let y = burst.y + spark.vy * t - 0.5 * GRAVITY * t * t;
+                (x, y, if is_fading { Color::DarkGray } else { spark.color })
+            })
+        })
+    }
+}
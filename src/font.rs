@@ -0,0 +1,121 @@
+use std::env;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use figlet_rs::FIGfont;
+
+use crate::app::AppResult;
+
+/// Directories searched for fonts given by name, after the bundled fonts and `$FIGLET_FONTDIR`.
+const FONT_DIRS: [&str; 2] = ["/usr/share/figlet", "/usr/local/share/figlet"];
+
+/// Small seven-segment FIGfont for the `--milliseconds` row, three lines tall.
+const SMALL_FONT: &str = include_str!("../assets/small.flf");
+
+/// FIGfonts built into clocktui, by name, so they work without FIGlet installed.
+const BUNDLED_FONTS: [(&str, &str); 3] = [
+    ("small", SMALL_FONT),
+    ("slant", include_str!("../assets/slant.flf")),
+    ("block", include_str!("../assets/block.flf")),
+];
+
+/// A FIGfont loaded once and shared by everything drawn with it.
+#[derive(Clone)]
+pub struct Font {
+    figfont: Arc<FIGfont>,
+    /// Name or path the font was loaded by.
+    name: String,
+}
+
+impl Font {
+    /// The standard FIGfont built into `figlet-rs`.
+    pub fn standard() -> Self {
+        Self { figfont: Arc::new(FIGfont::standand().unwrap()), name: "standard".to_string() }
+    }
+
+    /// The small seven-segment font built into clocktui.
+    pub fn small() -> Self {
+        Self { figfont: Arc::new(FIGfont::from_content(SMALL_FONT).unwrap()), name: "small".to_string() }
+    }
+
+    /// Loads the font named `font` from the bundled fonts, such as `slant`, or else the font at
+    /// the path `font` or named `font` in the FIGlet font directories.
+    pub fn load(font: &str) -> AppResult<Self> {
+        if font == "standard" {
+            return Ok(Self::standard());
+        }
+        if let Some((name, content)) = BUNDLED_FONTS.iter().find(|(name, _)| *name == font) {
+            return Ok(Self { figfont: Arc::new(FIGfont::from_content(content)?), name: name.to_string() });
+        }
+        let path = find_font(font).ok_or_else(|| {
+            let bundled: Vec<&str> = BUNDLED_FONTS.iter().map(|(name, _)| *name).collect();
+            format!(
+                "font `{}` not bundled (standard, {}) nor found in $FIGLET_FONTDIR, {} or as a file",
+                font, bundled.join(", "), FONT_DIRS.join(", ")
+            )
+        })?;
+        let figfont = FIGfont::from_file(&path.to_string_lossy())
+            .map_err(|e| format!("can't load font `{}`: {}", path.display(), e))?;
+        Ok(Self { figfont: Arc::new(figfont), name: font.to_string() })
+    }
+
+    /// `text` drawn in the font, falling back to the standard font when the font has none of its
+    /// characters, and to nothing when neither has.
+    pub fn figure(&self, text: &str) -> String {
+        static STANDARD: OnceLock<Font> = OnceLock::new();
+        self.convert(text)
+            .or_else(|| STANDARD.get_or_init(Self::standard).convert(text))
+            .map(|figure| figure.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Name or path the font was loaded by.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Deref for Font {
+    type Target = FIGfont;
+
+    fn deref(&self) -> &FIGfont {
+        &self.figfont
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+/// Path of the font file `font`, either as given or as `<font>.flf` in a font directory.
+fn find_font(font: &str) -> Option<PathBuf> {
+    let path = Path::new(font);
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    // A path that doesn't exist isn't looked up by name
+    if path.components().count() > 1 {
+        return None;
+    }
+    env::var_os("FIGLET_FONTDIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(FONT_DIRS.iter().map(PathBuf::from))
+        .map(|dir| dir.join(font).with_extension("flf"))
+        .find(|path| path.is_file())
+}
+
+/// Scales FIGfont text up by repeating each character `scale` times
+/// horizontally and each line `scale` times vertically.
+pub fn scale_figfont(s: &str, scale: u8) -> String {
+    let scale = scale.max(1) as usize;
+    s.split('\n')
+        .map(|line| line.chars().flat_map(|ch| std::iter::repeat_n(ch, scale)).collect::<String>())
+        .flat_map(|line| std::iter::repeat_n(line, scale))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
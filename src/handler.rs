@@ -1,19 +1,100 @@
 use crate::app::{App, AppResult};
+use crate::event::{Event, Events};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::Duration;
+
+/// Handles a single event of the main loop.
+pub fn handle_event(event: Event, app: &mut App, events: &impl Events) -> AppResult<()> {
+    match event {
+        Event::LogicTick(duration) => app.tick_logic(duration, events),
+        Event::RenderTick(duration) => app.tick_render(duration, events),
+        Event::Key(key_event) => handle_key_events(key_event, app)?,
+        Event::Mouse(mouse_event) => app.handle_mouse(mouse_event),
+        // Redraws straight away rather than on the next render tick, which may be a second off
+        Event::Resize(_, _) => events.send(Event::RenderTick(Duration::ZERO))?,
+        Event::Terminate => app.set_running(false),
+        Event::Format(format) => {
+            app.set_format(&format);
+            events.trigger_animation(true);
+        }
+        #[cfg(feature = "beat-sync")]
+        Event::Beat => app.beat(events),
+    }
+    Ok(())
+}
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    // type the PIN while the lock screen dialog is open
+    if let Some(lock) = app.pin_dialog() {
+        match key_event.code {
+            KeyCode::Char(digit) => lock.push(digit),
+            KeyCode::Backspace => lock.pop(),
+            KeyCode::Esc => lock.cancel(),
+            KeyCode::Enter => {
+                let is_unlocked = lock.submit();
+                app.set_running(!is_unlocked);
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
     match key_event.code {
         // exit application on ESC or q
         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
-            app.running = false;
+            app.quit();
         }
 
         // exit application on Ctrl-D
-        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.running = false;
-            }
+        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Char('c') | KeyCode::Char('C')
+            if key_event.modifiers == KeyModifiers::CONTROL =>
+        {
+            app.quit();
+        }
+
+        // switch to the next preset on Tab
+        KeyCode::Tab => {
+            app.next_tab();
+        }
+
+        // pause, reset and lap the stopwatch on Space, r and l, scrolling laps with the arrow keys
+        KeyCode::Char(' ') => {
+            app.toggle_stopwatch();
+        }
+        KeyCode::Char('r') => {
+            app.reset_stopwatch();
+        }
+        KeyCode::Char('l') => {
+            app.record_lap();
+        }
+        KeyCode::Up => {
+            app.scroll_laps(-1);
+        }
+        KeyCode::Down => {
+            app.scroll_laps(1);
+        }
+
+        // turn the transition direction on Left and Right
+        KeyCode::Left => {
+            app.turn_direction(-1);
+        }
+        KeyCode::Right => {
+            app.turn_direction(1);
+        }
+
+        // remove and add back world clocks on - and +
+        KeyCode::Char('-') => {
+            app.remove_world_clock();
+        }
+        KeyCode::Char('+') => {
+            app.add_world_clock();
+        }
+
+        // copy the time to the clipboard on c
+        #[cfg(feature = "clipboard")]
+        KeyCode::Char('c') => {
+            app.copy_time();
         }
         _ => {}
     }
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{Datelike, NaiveDate};
+use tui::style::Color;
+
+use crate::app::AppResult;
+use crate::color::parse_color;
+
+/// Colours for specific calendar dates, read from a TOML file of `"MM-DD" = "colour"` entries.
+#[derive(Debug, Clone)]
+pub struct HolidayColorMap {
+    /// File the colours are read from.
+    path: PathBuf,
+    /// Modification time of the file when last read.
+    modified: Option<SystemTime>,
+    /// Colours keyed by month and day.
+    colors: HashMap<(u32, u32), Color>,
+}
+
+impl HolidayColorMap {
+    /// Reads the colours from the file at `path`.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let mut map = Self { path: path.to_path_buf(), modified: None, colors: HashMap::new() };
+        map.read()?;
+        Ok(map)
+    }
+
+    /// Re-reads the file if it has been modified since it was last read.
+    pub fn reload_if_changed(&mut self) -> AppResult<()> {
+        if fs::metadata(&self.path)?.modified().ok() != self.modified {
+            self.read()?;
+        }
+        Ok(())
+    }
+
+    /// The colour for `date`, if it has one.
+    pub fn color_for(&self, date: NaiveDate) -> Option<Color> {
+        self.colors.get(&(date.month(), date.day())).copied()
+    }
+
+    /// Reads the file, keeping the current colours if it is invalid.
+    fn read(&mut self) -> AppResult<()> {
+        let modified = fs::metadata(&self.path)?.modified().ok();
+        let entries: HashMap<String, String> = toml::from_str(&fs::read_to_string(&self.path)?)?;
+        let mut colors = HashMap::new();
+        for (date, color) in entries {
+            let month_day = date
+                .split_once('-')
+                .and_then(|(month, day)| Some((month.parse::<u32>().ok()?, day.parse::<u32>().ok()?)))
+                .filter(|&(month, day)| NaiveDate::from_ymd_opt(2000, month, day).is_some())
+                .ok_or_else(|| format!("invalid date `{}`, expected MM-DD", date))?;
+            colors.insert(month_day, parse_color(&color)?);
+        }
+        self.colors = colors;
+        self.modified = modified;
+        Ok(())
+    }
+}
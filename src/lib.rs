@@ -1,11 +1,113 @@
+pub use app::{AnimatedTime, App, Token, TokenBlock};
+pub use config::AppConfig;
+pub use event::{Event, EventHandler};
+
 /// Application.
 pub mod app;
 
+/// Application configuration.
+pub mod config;
+
+/// Building the application configuration in code.
+pub mod builder;
+
+/// Colour parsing.
+pub mod color;
+
+/// Falling confetti.
+pub mod confetti;
+
+/// Transition easing curves.
+pub mod easing;
+
+/// Static image export.
+pub mod export;
+
+/// Fireworks bursts.
+pub mod fireworks;
+
+/// FIGfont text helpers.
+pub mod font;
+
 /// Terminal events handler.
 pub mod event;
 
+/// Colours for calendar dates.
+pub mod holiday;
+
+/// Logging to a file.
+#[cfg(feature = "debug")]
+pub mod log;
+
+/// PIN lock screen.
+pub mod lock;
+
+/// Clock presets.
+pub mod preset;
+
+/// Render tick precision statistics.
+pub mod precision;
+
+/// Canvas shapes.
+pub mod shape;
+
+/// ASCII art slideshows.
+pub mod slideshow;
+
+/// Pausable stopwatch.
+pub mod stopwatch;
+
+/// Style sheets for the clock face.
+pub mod style;
+
+/// Sunrise and sunset times.
+pub mod sun;
+
+/// Digit transition physics.
+pub mod transition;
+
+/// Morse code playback.
+pub mod morse;
+
+/// Lunar phases.
+pub mod moon;
+
+/// Heap usage tracking.
+#[cfg(feature = "mem-limit")]
+pub mod memory;
+
+/// Voronoi diagram backgrounds.
+pub mod voronoi;
+
+/// Watched shell commands.
+pub mod watch;
+
 /// Terminal user interface.
 pub mod tui;
 
 /// Event handler.
 pub mod handler;
+
+/// RSS news ticker.
+#[cfg(feature = "network")]
+pub mod news;
+
+/// Current weather from wttr.in.
+#[cfg(feature = "network")]
+pub mod weather;
+
+/// NTP round trip times.
+#[cfg(feature = "network")]
+pub mod ntp;
+
+/// Audio file playback.
+#[cfg(feature = "audio")]
+pub mod audio;
+
+/// Audio beat detection.
+#[cfg(feature = "beat-sync")]
+pub mod beat;
+
+/// System clipboard access.
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
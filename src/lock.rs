@@ -0,0 +1,59 @@
+use sha2::{Digest, Sha256};
+
+/// Guards exiting the application behind a PIN.
+#[derive(Debug)]
+pub struct PinLock {
+    /// SHA-256 hash of the PIN, so the PIN itself isn't kept around.
+    hash: [u8; 32],
+    /// Digits typed so far, while the PIN dialog is open.
+    entry: Option<String>,
+    /// Whether the last submitted PIN was wrong.
+    pub failed: bool,
+}
+
+impl PinLock {
+    /// Constructs a new instance of [`PinLock`].
+    pub fn new(pin: &str) -> Self {
+        Self { hash: Sha256::digest(pin.as_bytes()).into(), entry: None, failed: false }
+    }
+
+    /// Opens the PIN dialog.
+    pub fn open(&mut self) {
+        self.entry = Some(String::new());
+    }
+
+    /// Closes the PIN dialog, discarding the entry.
+    pub fn cancel(&mut self) {
+        self.entry = None;
+        self.failed = false;
+    }
+
+    /// Digits typed so far, if the PIN dialog is open.
+    pub fn entry(&self) -> Option<&str> {
+        self.entry.as_deref()
+    }
+
+    /// Appends a digit to the entry.
+    pub fn push(&mut self, digit: char) {
+        if let Some(entry) = &mut self.entry {
+            if digit.is_ascii_digit() {
+                entry.push(digit);
+            }
+        }
+    }
+
+    /// Removes the last digit of the entry.
+    pub fn pop(&mut self) {
+        if let Some(entry) = &mut self.entry {
+            entry.pop();
+        }
+    }
+
+    /// Checks the entry against the PIN, clearing it.
+    pub fn submit(&mut self) -> bool {
+        let entry = self.entry.replace(String::new()).unwrap_or_default();
+        let is_correct = <[u8; 32]>::from(Sha256::digest(entry.as_bytes())) == self.hash;
+        self.failed = !is_correct;
+        is_correct
+    }
+}
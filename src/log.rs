@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+
+use crate::app::AppResult;
+
+/// Sends log output at `level` and above to a file at `path`, starting a new file every day.
+///
+/// Logging stops when the returned guard is dropped, after flushing anything still buffered.
+pub fn init(path: &Path, level: Level) -> AppResult<WorkerGuard> {
+    let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let file_name = path.file_name().ok_or_else(|| format!("`{}` is not a file path", path.display()))?;
+    let (writer, guard) = tracing_appender::non_blocking(tracing_appender::rolling::daily(directory, file_name));
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_target(true)
+        .with_max_level(level)
+        .try_init()
+        .map_err(|e| e.to_string())?;
+    Ok(guard)
+}
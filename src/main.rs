@@ -1,21 +1,81 @@
-use std::io;
+use std::io::{self, Write};
+use std::time::Duration;
 use tui::backend::CrosstermBackend;
 use tui::Terminal;
-use clocktui::app::{App, AppResult};
-use clocktui::event::{Event, EventHandler};
-use clocktui::handler::handle_key_events;
-use clocktui::tui::Tui;
+use crossterm::tty::IsTty;
+use clocktui::app::{App, AppResult, BENCHMARK_FRAMES};
+#[cfg(feature = "beat-sync")]
+use clocktui::beat::BeatDetector;
+use clocktui::config::AppConfig;
+use clocktui::event::{Event, EventHandler, IDLE_RENDER_TICK_RATE, TICK_RATE};
+use clocktui::handler::handle_event;
+use clocktui::tui::{ClearMode, Tui};
+
+/// Counts heap usage for `--memory-limit`.
+#[cfg(feature = "mem-limit")]
+#[global_allocator]
+static ALLOCATOR: clocktui::memory::TrackingAllocator = clocktui::memory::TrackingAllocator;
 
 fn main() -> AppResult<()> {
-    // Create an application.q
-    let mut app = App::default();
+    // Create an application.
+    let config = AppConfig::parse_with_preset(std::env::args_os());
+    #[cfg(feature = "debug")]
+    let _log_guard = config.log_to_file.as_deref().map(|path| clocktui::log::init(path, config.log_level)).transpose()?;
+    #[cfg(feature = "debug")]
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "starting");
+    if config.export_svg.is_some() || config.export_png.is_some() {
+        let mut app = App::new(config.clone())?;
+        if let Some(path) = &config.export_svg {
+            app.export_svg(path)?;
+        }
+        if let Some(path) = &config.export_png {
+            app.export_png(path)?;
+        }
+        return Ok(());
+    }
+    if config.benchmark {
+        let mut app = App::new(config)?;
+        let fps = app.benchmark(BENCHMARK_FRAMES)?;
+        println!("Benchmark: {:.0} fps (font {}, format {})", fps, app.font_name(), app.format_string());
+        return Ok(());
+    }
+    if let Some(tick_rate) = config.tick_rate.filter(|&tick_rate| tick_rate < 1000) {
+        writeln!(io::stderr(), "Warning: a --tick-rate of {} ms ticks faster than once a second, but most formats only change once a second", tick_rate).ok();
+    }
+    if config.accessible && io::stdout().is_tty() {
+        return Err("--accessible writes to stdout, which has to be redirected away from the terminal the clock is drawn on".into());
+    }
+    let mut app = App::new(config)?;
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend).expect("Failed to interface with the terminal");
-    let events = EventHandler::new(200, 20);
-    let mut tui = Tui::new(terminal, events);
+    // The stopwatch speeds up render ticks itself while it runs
+    let render_tick_rate = app.capped_render_tick_rate(app.render_rate());
+    let idle_render_tick_rate = (!app.config().no_idle_throttle).then_some(IDLE_RENDER_TICK_RATE);
+    let events = EventHandler::with_idle_render_rate(app.config().tick_rate.unwrap_or(TICK_RATE), render_tick_rate, idle_render_tick_rate)
+        .set_max_fps(app.frame_rate_limit());
+    app.set_render_tick_rate(events.render_tick_rate());
+    let mut tui = Tui::new(terminal, events)
+        .set_overlay(app.config().overlay_mode)
+        .set_overlay_line(app.config().line)
+        .set_startup_delay(Duration::from_millis(app.config().startup_delay))
+        .set_clear_mode(match (app.config().no_clear, app.config().full_clear) {
+            (true, _) => ClearMode::Never,
+            (_, true) => ClearMode::EveryFrame,
+            _ => ClearMode::Init
+        });
     tui.init()?;
+    #[cfg(unix)]
+    tui.events.forward_signals()?;
+    if app.config().stdin_format {
+        tui.events.forward_stdin_formats();
+    }
+
+    #[cfg(feature = "beat-sync")]
+    if app.config().beat_sync {
+        BeatDetector::new()?.forward_to(tui.events.sender());
+    }
 
     // Start the main loop.
     while app.running {
@@ -23,11 +83,13 @@ fn main() -> AppResult<()> {
         tui.draw(&mut app)?;
         // Handle events.
         match tui.events.next()? {
-            Event::LogicTick(duration) => app.tick_logic(duration, &tui.events),
-            Event::RenderTick(duration) => app.tick_render(duration, &tui.events),
-            Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+            Event::Resize(width, height) => {
+                if app.config().restart_on_resize {
+                    tui.restart()?;
+                }
+                handle_event(Event::Resize(width, height), &mut app, &tui.events)?;
+            }
+            event => handle_event(event, &mut app, &tui.events)?,
         }
     }
 
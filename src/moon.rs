@@ -0,0 +1,31 @@
+use chrono::{DateTime, TimeZone};
+
+/// Length of a lunar cycle, from new moon to new moon, in days.
+const SYNODIC_MONTH: f64 = 29.530588853;
+/// Julian day of a known new moon, on 6 January 2000 at 18:14 UTC.
+const NEW_MOON_EPOCH: f64 = 2451550.26;
+/// Julian day of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+/// Symbol and name of each phase, starting from the new moon.
+const PHASES: [(char, &str); 8] = [
+    ('🌑', "New Moon"),
+    ('🌒', "Waxing Crescent"),
+    ('🌓', "First Quarter"),
+    ('🌔', "Waxing Gibbous"),
+    ('🌕', "Full Moon"),
+    ('🌖', "Waning Gibbous"),
+    ('🌗', "Last Quarter"),
+    ('🌘', "Waning Crescent"),
+];
+
+/// Julian day of `dt`, counting fractional days.
+pub fn julian_day<Tz: TimeZone>(dt: &DateTime<Tz>) -> f64 {
+    UNIX_EPOCH_JD + dt.timestamp_millis() as f64 / 86_400_000.0
+}
+
+/// Symbol and name of the lunar phase on Julian day `jdn`, to the nearest eighth of a cycle.
+pub fn moon_phase(jdn: f64) -> (char, &'static str) {
+    let age = ((jdn - NEW_MOON_EPOCH) / SYNODIC_MONTH).rem_euclid(1.0);
+    PHASES[(age * PHASES.len() as f64).round() as usize % PHASES.len()]
+}
@@ -0,0 +1,92 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// Length of a dot, the base unit of Morse timing.
+const DOT: Duration = Duration::from_millis(80);
+
+/// Morse code for a character, if it has one.
+fn code(ch: char) -> Option<&'static str> {
+    Some(match ch.to_ascii_uppercase() {
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        _ => return None,
+    })
+}
+
+/// Converts `time_str` to a schedule of tones (`true`) and silences (`false`).
+///
+/// Timings follow ITU: a dot is one unit and a dash three, with one unit between
+/// elements, three between characters and seven between words. Characters
+/// without a Morse code, such as `:`, separate words.
+pub fn morse_schedule(time_str: &str) -> Vec<(bool, Duration)> {
+    let mut schedule: Vec<(bool, Duration)> = Vec::new();
+    let mut gap = Duration::ZERO;
+    for ch in time_str.chars() {
+        let code = match code(ch) {
+            Some(code) => code,
+            None => {
+                gap = DOT * 7;
+                continue;
+            }
+        };
+        for element in code.chars() {
+            if !schedule.is_empty() {
+                schedule.push((false, gap.max(DOT)));
+            }
+            schedule.push((true, if element == '.' { DOT } else { DOT * 3 }));
+            gap = DOT;
+        }
+        gap = DOT * 3;
+    }
+    schedule
+}
+
+/// Plays `schedule` on the terminal bell in a background thread.
+///
+/// The bell can't be held, so each tone rings once at its start.
+pub fn play_bell(schedule: Vec<(bool, Duration)>) {
+    thread::spawn(move || {
+        for (is_tone, duration) in schedule {
+            if is_tone {
+                let mut stderr = io::stderr();
+                stderr.write_all(b"\x07").and_then(|_| stderr.flush()).ok();
+            }
+            thread::sleep(duration);
+        }
+    });
+}
@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::app::AppResult;
+
+/// How often the feed is fetched again.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How fast the headlines scroll, in terminal cells per second.
+const SCROLL_SPEED: f64 = 10.0;
+/// Text between neighbouring headlines.
+const SEPARATOR: &str = "  •  ";
+
+/// Headlines of an RSS feed scrolling past as a marquee.
+#[derive(Debug)]
+pub struct NewsTicker {
+    /// Latest headlines, replaced by the background thread on every refresh.
+    headlines: Arc<Mutex<Vec<String>>>,
+    /// Distance scrolled, in terminal cells.
+    offset: f64,
+}
+
+impl NewsTicker {
+    /// Starts fetching the feed at `url` in the background, refreshing it every five minutes.
+    pub fn new(url: &str) -> Self {
+        let headlines = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::downgrade(&headlines);
+        let url = url.to_string();
+        thread::spawn(move || loop {
+            let fetched = fetch_headlines(&url);
+            // Stop once the ticker is gone
+            let Some(headlines) = shared.upgrade() else { return };
+            // A failed refresh keeps the old headlines scrolling
+            if let Ok(fetched) = fetched {
+                *headlines.lock().unwrap() = fetched;
+            }
+            drop(headlines);
+            thread::sleep(REFRESH_INTERVAL);
+        });
+        Self { headlines, offset: 0.0 }
+    }
+
+    /// Scrolls the marquee along by `dt`, returning whether there is anything to scroll.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        self.offset += SCROLL_SPEED * dt.as_secs_f64();
+        !self.headlines.lock().unwrap().is_empty()
+    }
+
+    /// The `width` cells of the marquee currently in view.
+    pub fn text(&self, width: u16) -> String {
+        let headlines = self.headlines.lock().unwrap();
+        if headlines.is_empty() {
+            return String::new();
+        }
+        let marquee: Vec<char> = headlines.iter().flat_map(|headline| headline.chars().chain(SEPARATOR.chars())).collect();
+        let start = self.offset as usize % marquee.len();
+        marquee.iter().cycle().skip(start).take(width as usize).collect()
+    }
+}
+
+/// Downloads the feed at `url` and returns its headlines.
+fn fetch_headlines(url: &str) -> AppResult<Vec<String>> {
+    let body = ureq::get(url).call()?.into_string()?;
+    Ok(parse_headlines(&body)?)
+}
+
+/// Titles of the items of an RSS feed, or the entries of an Atom feed, leaving out the title of
+/// the feed itself.
+pub fn parse_headlines(feed: &str) -> Result<Vec<String>, roxmltree::Error> {
+    let document = roxmltree::Document::parse(feed)?;
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("title"))
+        .filter(|node| node.parent_element().is_some_and(|parent| matches!(parent.tag_name().name(), "item" | "entry")))
+        .filter_map(|node| node.text())
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|title| !title.is_empty())
+        .collect())
+}
@@ -0,0 +1,73 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Port NTP servers listen on.
+const NTP_PORT: u16 = 123;
+/// How often the server is queried.
+const QUERY_INTERVAL: Duration = Duration::from_secs(1);
+/// How long to wait for a reply before giving up on a query.
+const TIMEOUT: Duration = Duration::from_secs(1);
+/// Size of an NTP packet without extensions, in bytes.
+const PACKET_SIZE: usize = 48;
+
+/// Round trip time to an NTP server, measured in the background once a second.
+#[derive(Debug)]
+pub struct NtpMonitor {
+    /// Latest round trip time, or `None` if the last query went unanswered.
+    rtt: Arc<Mutex<Option<Duration>>>,
+}
+
+impl NtpMonitor {
+    /// Starts querying the NTP server `server` in the background.
+    pub fn new(server: &str) -> Self {
+        let rtt = Arc::new(Mutex::new(None));
+        let shared = Arc::downgrade(&rtt);
+        let server = server.to_string();
+        thread::spawn(move || loop {
+            let queried = Instant::now();
+            let measured = round_trip((server.as_str(), NTP_PORT)).ok();
+            // Stop once the monitor is gone
+            let Some(rtt) = shared.upgrade() else { return };
+            *rtt.lock().unwrap() = measured;
+            drop(rtt);
+            thread::sleep(QUERY_INTERVAL.saturating_sub(queried.elapsed()));
+        });
+        Self { rtt }
+    }
+
+    /// Latest round trip time, or `None` if the server can't be reached.
+    pub fn rtt(&self) -> Option<Duration> {
+        *self.rtt.lock().unwrap()
+    }
+}
+
+/// Sends an SNTP client request to the server at `addr` and times how long the reply takes.
+pub fn round_trip(addr: impl ToSocketAddrs) -> io::Result<Duration> {
+    let addr = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "NTP server has no address"))?;
+    let local: SocketAddr = match addr {
+        SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+        SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    socket.connect(addr)?;
+    let mut request = [0; PACKET_SIZE];
+    // Leap indicator 0, version 3, client mode
+    request[0] = 0x1b;
+    let sent = Instant::now();
+    socket.send(&request)?;
+    let mut reply = [0; PACKET_SIZE];
+    let len = socket.recv(&mut reply)?;
+    let rtt = sent.elapsed();
+    // Anything but a full packet in server mode isn't an answer to the request
+    if len < PACKET_SIZE || reply[0] & 0b111 != 4 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not an NTP server reply"));
+    }
+    Ok(rtt)
+}
@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of ticks the statistics are computed over.
+const WINDOW: usize = 60;
+
+/// Rolling statistics on how closely render ticks keep to their target interval.
+#[derive(Debug, Clone, Default)]
+pub struct TickPrecision {
+    /// Intervals between the most recent ticks, oldest first.
+    intervals: VecDeque<Duration>,
+}
+
+impl TickPrecision {
+    /// Records the interval since the previous tick.
+    pub fn record(&mut self, interval: Duration) {
+        if self.intervals.len() == WINDOW {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(interval);
+    }
+
+    /// Average tick rate, in ticks per second.
+    pub fn average_rate(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        (mean > 0.0).then(|| 1.0 / mean)
+    }
+
+    /// Standard deviation of the tick interval, in seconds.
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let variance = self.intervals
+            .iter()
+            .map(|interval| (interval.as_secs_f64() - mean).powi(2))
+            .sum::<f64>() / self.intervals.len() as f64;
+        Some(variance.sqrt())
+    }
+
+    /// Largest deviation of a tick interval from `target`, in seconds.
+    pub fn max_jitter(&self, target: Duration) -> Option<f64> {
+        self.intervals
+            .iter()
+            .map(|interval| (interval.as_secs_f64() - target.as_secs_f64()).abs())
+            .reduce(f64::max)
+    }
+
+    /// Mean tick interval, in seconds.
+    fn mean(&self) -> Option<f64> {
+        if self.intervals.is_empty() {
+            return None;
+        }
+        Some(self.intervals.iter().map(Duration::as_secs_f64).sum::<f64>() / self.intervals.len() as f64)
+    }
+}
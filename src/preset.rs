@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::Path;
+
+use chrono_tz::Tz;
+use serde::Deserialize;
+use tui::style::Color;
+
+use crate::app::AppResult;
+use crate::color::parse_color;
+use crate::font::Font;
+
+/// A clock preset selectable as a tab, read from a `[[preset]]` table of the presets file.
+#[derive(Debug, Clone)]
+pub struct Preset {
+    /// Name shown in the tab bar.
+    pub name: String,
+    /// `strftime` format string of the time.
+    pub format: String,
+    /// Colour of the clock, if set.
+    pub color: Option<Color>,
+    /// Timezone of the clock, if set, rather than `--timezone`.
+    pub timezone: Option<Tz>,
+    /// FIGlet font of the clock, if set, rather than `--font`.
+    pub font: Option<Font>,
+}
+
+/// A `[[preset]]` table as written in the presets file.
+#[derive(Debug, Deserialize)]
+struct PresetEntry {
+    name: String,
+    #[serde(default = "default_format")]
+    format: String,
+    color: Option<String>,
+    timezone: Option<String>,
+    font: Option<String>,
+}
+
+/// The presets file.
+#[derive(Debug, Deserialize)]
+struct PresetFile {
+    #[serde(default)]
+    preset: Vec<PresetEntry>,
+}
+
+fn default_format() -> String {
+    "%X".to_string()
+}
+
+/// Reads the presets from the TOML file at `path`.
+pub fn load(path: &Path) -> AppResult<Vec<Preset>> {
+    let file: PresetFile = toml::from_str(&fs::read_to_string(path)?)?;
+    if file.preset.is_empty() {
+        return Err(format!("no [[preset]] tables in `{}`", path.display()).into());
+    }
+    file.preset
+        .into_iter()
+        .map(|entry| {
+            Ok(Preset {
+                name: entry.name,
+                format: entry.format,
+                color: entry.color.as_deref().map(parse_color).transpose()?,
+                timezone: entry.timezone.as_deref().map(str::parse::<Tz>).transpose()?,
+                font: entry.font.as_deref().map(Font::load).transpose()?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Color;
+use tui::widgets::canvas::{Context, Line, Painter, Shape};
+use tui::widgets::Widget;
+
+/// Number of points sampled along the outline of a [`Circle`].
+const CIRCLE_SAMPLES: u32 = 720;
+
+/// Outline of a circle, for drawing on a [`Canvas`](tui::widgets::canvas::Canvas).
+#[derive(Debug, Clone, Copy)]
+pub struct Circle {
+    /// X coordinate of the centre.
+    pub x: f64,
+    /// Y coordinate of the centre.
+    pub y: f64,
+    /// Radius, in canvas units.
+    pub radius: f64,
+    /// Colour of the outline.
+    pub color: Color,
+}
+
+impl Shape for Circle {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for sample in 0..CIRCLE_SAMPLES {
+            let angle = std::f64::consts::TAU * sample as f64 / CIRCLE_SAMPLES as f64;
+            let x = self.x + self.radius * angle.cos();
+            let y = self.y + self.radius * angle.sin();
+            if let Some((x, y)) = painter.get_point(x, y) {
+                painter.paint(x, y, self.color);
+            }
+        }
+    }
+}
+
+/// Angle between the line segments approximating an arc, in radians.
+const ARC_STEP: f64 = std::f64::consts::TAU / 360.0;
+
+/// Draws an arc of radius `r` around (`cx`, `cy`) from angle `start` to `end`.
+///
+/// Angles are in radians, measured clockwise from 12 o'clock.
+pub fn draw_arc(ctx: &mut Context, cx: f64, cy: f64, r: f64, start: f64, end: f64) {
+    let segments = ((end - start).abs() / ARC_STEP).ceil().max(1.0) as u32;
+    let point = |angle: f64| (cx + r * angle.sin(), cy + r * angle.cos());
+    for segment in 0..segments {
+        let (x1, y1) = point(start + (end - start) * segment as f64 / segments as f64);
+        let (x2, y2) = point(start + (end - start) * (segment + 1) as f64 / segments as f64);
+        ctx.draw(&Line { x1, y1, x2, y2, color: Color::Reset });
+    }
+}
+
+/// Number of dashes around each ring drawn by [`draw_ring`].
+const RING_DASHES: u32 = 6;
+
+/// Draws a dashed ring of radius `r` around (`cx`, `cy`), turned by `phase` radians clockwise so
+/// rotation shows.
+pub fn draw_ring(ctx: &mut Context, cx: f64, cy: f64, r: f64, phase: f64) {
+    let spacing = std::f64::consts::TAU / RING_DASHES as f64;
+    for dash in 0..RING_DASHES {
+        let start = phase + spacing * dash as f64;
+        // Dashes and gaps are the same length
+        draw_arc(ctx, cx, cy, r, start, start + spacing / 2.0);
+    }
+}
+
+/// Widget that draws only the cells another widget paints, such as the points of a
+/// [`Canvas`](tui::widgets::canvas::Canvas), keeping the background already drawn beneath.
+///
+/// A canvas resets the background of its whole area, which would wipe out `--gradient-bg` and
+/// `--bg`.
+pub struct Transparent<W>(pub W);
+
+impl<W: Widget> Widget for Transparent<W> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut painted = Buffer::empty(area);
+        self.0.render(area, &mut painted);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = painted.get(x, y);
+                // Blank braille is what a canvas leaves where nothing was painted
+                if cell.symbol != " " && cell.symbol != "\u{2800}" {
+                    buf.get_mut(x, y).set_symbol(&cell.symbol).set_fg(cell.fg);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+
+use crate::app::AppResult;
+
+/// ASCII art files from a directory shown one after another, in random order.
+#[derive(Debug)]
+pub struct Slideshow {
+    /// Text of the image being shown.
+    current: String,
+    /// Next image, loaded ahead of time by a background thread.
+    next: Receiver<String>,
+    /// How long each image is shown for.
+    interval: Duration,
+    /// When the current image was first shown.
+    shown_since: Instant,
+}
+
+impl Slideshow {
+    /// Starts a slideshow of the files in `dir`, showing each for `interval`.
+    pub fn new(dir: &Path, interval: Duration) -> AppResult<Self> {
+        let paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        if paths.is_empty() {
+            return Err(format!("no files to show in `{}`", dir.display()).into());
+        }
+        // Room for just one image, so the thread stays one image ahead of the slideshow
+        let (sender, next) = mpsc::sync_channel(1);
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            let mut paths = paths;
+            // Stops if none of the files can be read, rather than spinning through them forever
+            let mut is_any_shown = true;
+            while is_any_shown {
+                is_any_shown = false;
+                paths.shuffle(&mut rng);
+                for path in &paths {
+                    // Files that can't be read are skipped rather than shown blank
+                    let Ok(image) = fs::read_to_string(path) else { continue };
+                    if sender.send(image).is_err() {
+                        return;
+                    }
+                    is_any_shown = true;
+                }
+            }
+        });
+        let current = next.recv().map_err(|_| format!("no readable files to show in `{}`", dir.display()))?;
+        Ok(Self { current, next, interval, shown_since: Instant::now() })
+    }
+
+    /// Moves on to the next image once the current one has been shown for long enough.
+    ///
+    /// Keeps showing the current image if the next one hasn't finished loading.
+    pub fn tick(&mut self) {
+        if self.shown_since.elapsed() < self.interval {
+            return;
+        }
+        if let Ok(image) = self.next.try_recv() {
+            self.current = image;
+            self.shown_since = Instant::now();
+        }
+    }
+
+    /// Text of the image being shown.
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+/// A stopwatch that can be paused and reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stopwatch {
+    /// Time counted up to when the stopwatch was last started.
+    counted: Duration,
+    /// When the stopwatch was last started, if it is running.
+    started: Option<Instant>,
+}
+
+impl Stopwatch {
+    /// Constructs a stopwatch already running from now.
+    pub fn start() -> Self {
+        Self { counted: Duration::ZERO, started: Some(Instant::now()) }
+    }
+
+    /// Total time the stopwatch has been running for.
+    pub fn elapsed(&self) -> Duration {
+        self.counted + self.started.map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    /// Whether the stopwatch is counting.
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Pauses the stopwatch if it is running, or resumes it if paused.
+    pub fn toggle(&mut self) {
+        match self.started.take() {
+            Some(started) => self.counted += started.elapsed(),
+            None => self.started = Some(Instant::now())
+        }
+    }
+
+    /// Sets the stopwatch back to zero, leaving it running or paused.
+    pub fn reset(&mut self) {
+        self.counted = Duration::ZERO;
+        if self.started.is_some() {
+            self.started = Some(Instant::now());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use clap::ValueEnum;
+use tui::style::{Color, Style};
+use tui::widgets::BorderType;
+
+use crate::app::AppResult;
+use crate::color::parse_color;
+use crate::config::BorderStyle;
+
+/// Colours and borders of one kind of block, from a rule of the style sheet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleRule {
+    /// Foreground colour, from `color`.
+    pub color: Option<Color>,
+    /// Background colour, from `background`.
+    pub background: Option<Color>,
+    /// Borders of the box, from `border`.
+    pub border: Option<BorderStyle>,
+}
+
+impl StyleRule {
+    /// The colours of the rule as a style to patch others with.
+    pub fn style(&self) -> Style {
+        let style = Style::default();
+        let style = match self.color {
+            Some(color) => style.fg(color),
+            None => style
+        };
+        match self.background {
+            Some(color) => style.bg(color),
+            None => style
+        }
+    }
+
+    /// Borders of the box, `None` if the rule leaves them out or doesn't say, and `Some(None)` for
+    /// no box at all.
+    pub fn border_type(&self) -> Option<Option<BorderType>> {
+        self.border.map(BorderStyle::border_type)
+    }
+}
+
+/// Styles of the clock face, read from a CSS-like style sheet of `.digit`, `.constant` and
+/// `.transition` rules, such as `.digit { color: cyan; border: double; }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleSheet {
+    /// Style of digit blocks.
+    pub digit: StyleRule,
+    /// Style of constant blocks, such as separators.
+    pub constant: StyleRule,
+    /// Style of new tokens as they transition in.
+    pub transition: StyleRule,
+}
+
+impl StyleSheet {
+    /// Parses a style sheet, made up of rules of `property: value;` declarations in braces.
+    ///
+    /// Supports `/* */` comments and the `color`, `background` and `border` properties, though
+    /// `.constant` takes no `border`.
+    pub fn parse(sheet: &str) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut rest = strip_comments(sheet);
+        while let Some((selector, after)) = rest.split_once('{') {
+            let (body, after) = after
+                .split_once('}')
+                .ok_or_else(|| format!("unclosed rule `{}`", selector.trim()))?;
+            let selector = selector.trim();
+            let rule = match selector {
+                ".digit" => &mut parsed.digit,
+                ".constant" => &mut parsed.constant,
+                ".transition" => &mut parsed.transition,
+                selector => return Err(format!("unknown selector `{}`, expected .digit, .constant or .transition", selector))
+            };
+            for declaration in body.split(';').map(str::trim).filter(|declaration| !declaration.is_empty()) {
+                let (property, value) = declaration
+                    .split_once(':')
+                    .ok_or_else(|| format!("invalid declaration `{}`, expected PROPERTY: VALUE", declaration))?;
+                let value = value.trim();
+                match property.trim() {
+                    "color" => rule.color = Some(parse_color(value).map_err(|e| e.to_string())?),
+                    "background" => rule.background = Some(parse_color(value).map_err(|e| e.to_string())?),
+                    "border" if selector == ".constant" => return Err("`.constant` takes no `border`, as constant blocks have no box".to_string()),
+                    "border" => rule.border = Some(BorderStyle::from_str(value, true)?),
+                    property => return Err(format!("unknown property `{}`, expected color, background or border", property))
+                }
+            }
+            rest = after.to_string();
+        }
+        if !rest.trim().is_empty() {
+            return Err(format!("unexpected `{}` outside of a rule", rest.trim()));
+        }
+        Ok(parsed)
+    }
+}
+
+/// A [`StyleSheet`] read from a file, reloaded whenever the file changes.
+#[derive(Debug, Clone)]
+pub struct StyleFile {
+    /// File the style sheet is read from.
+    path: PathBuf,
+    /// Modification time of the file when last read.
+    modified: Option<SystemTime>,
+    /// Style sheet last read from the file.
+    sheet: StyleSheet,
+}
+
+impl StyleFile {
+    /// Reads the style sheet from the file at `path`.
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let mut file = Self { path: path.to_path_buf(), modified: None, sheet: StyleSheet::default() };
+        file.read()?;
+        Ok(file)
+    }
+
+    /// Re-reads the file if it has been modified since it was last read.
+    pub fn reload_if_changed(&mut self) -> AppResult<()> {
+        if fs::metadata(&self.path)?.modified().ok() != self.modified {
+            self.read()?;
+        }
+        Ok(())
+    }
+
+    /// Style sheet last read from the file.
+    pub fn sheet(&self) -> &StyleSheet {
+        &self.sheet
+    }
+
+    /// Reads the file, keeping the current style sheet if it is invalid.
+    fn read(&mut self) -> AppResult<()> {
+        let modified = fs::metadata(&self.path)?.modified().ok();
+        let sheet = StyleSheet::parse(&fs::read_to_string(&self.path)?)
+            .map_err(|e| format!("invalid style sheet `{}`: {}", self.path.display(), e))?;
+        self.sheet = sheet;
+        self.modified = modified;
+        Ok(())
+    }
+}
+
+/// `sheet` with its `/* */` comments taken out.
+fn strip_comments(sheet: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = sheet;
+    while let Some((before, after)) = rest.split_once("/*") {
+        stripped.push_str(before);
+        // An unclosed comment runs to the end of the sheet
+        rest = after.split_once("*/").map_or("", |(_, after)| after);
+    }
+    stripped.push_str(rest);
+    stripped
+}
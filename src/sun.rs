@@ -0,0 +1,45 @@
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+/// Julian day of noon UTC on 1 January 2000.
+const J2000: f64 = 2451545.0;
+/// Tilt of the Earth's axis, in degrees.
+const OBLIQUITY: f64 = 23.4397;
+/// Altitude of the sun's centre at sunrise and sunset, allowing for refraction and the size of
+/// the sun's disc, in degrees.
+const HORIZON: f64 = -0.833;
+
+/// Time of sunrise on `date` at latitude `lat` and longitude `lon` (east positive), in UTC, or
+/// `None` if the sun doesn't rise or set that day.
+pub fn sunrise(lat: f64, lon: f64, date: NaiveDate) -> Option<NaiveTime> {
+    let (transit, hour_angle) = solar_transit(lat, lon, date)?;
+    Some(utc_time(transit - hour_angle / 360.0))
+}
+
+/// Time of sunset on `date` at latitude `lat` and longitude `lon` (east positive), in UTC, or
+/// `None` if the sun doesn't rise or set that day.
+pub fn sunset(lat: f64, lon: f64, date: NaiveDate) -> Option<NaiveTime> {
+    let (transit, hour_angle) = solar_transit(lat, lon, date)?;
+    Some(utc_time(transit + hour_angle / 360.0))
+}
+
+/// Julian day of solar noon and the hour angle of sunrise, in degrees, from the sunrise equation.
+fn solar_transit(lat: f64, lon: f64, date: NaiveDate) -> Option<(f64, f64)> {
+    let days = (date - NaiveDate::from_ymd(2000, 1, 1)).num_days() as f64 + 0.0008;
+    let mean_noon = days - lon / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_noon).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = J2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * longitude).sin();
+    let declination = (longitude.sin() * OBLIQUITY.to_radians().sin()).asin();
+    let lat = lat.to_radians();
+    let cos_hour_angle = (HORIZON.to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    // Beyond ±1 the sun stays above or below the horizon all day
+    (-1.0..=1.0).contains(&cos_hour_angle).then(|| (transit, cos_hour_angle.acos().to_degrees()))
+}
+
+/// Time of day in UTC of Julian day `jd`.
+fn utc_time(jd: f64) -> NaiveTime {
+    // Julian days start at noon
+    let seconds = ((jd + 0.5).rem_euclid(1.0) * 86400.0).round() as i64;
+    NaiveTime::from_hms(0, 0, 0) + Duration::seconds(seconds)
+}
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::Rng;
+use tui::layout::Direction;
+
+/// Edge a new token wipes in from over the old one.
+///
+/// Numbered 0 to 3 from the command line, alternating between vertical and horizontal wipes so
+/// that stepping the number by one turns the wipe a quarter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WipeDirection {
+    /// Vertical, from the leading (top) edge down.
+    #[default]
+    TopDown,
+    /// Horizontal, from the leading (left) edge across.
+    LeftRight,
+    /// Vertical, from the trailing (bottom) edge up.
+    BottomUp,
+    /// Horizontal, from the trailing (right) edge across.
+    RightLeft,
+}
+
+impl WipeDirection {
+    /// Every direction, in the order they are numbered.
+    const ALL: [WipeDirection; 4] = [WipeDirection::TopDown, WipeDirection::LeftRight, WipeDirection::BottomUp, WipeDirection::RightLeft];
+
+    /// The direction numbered `index`, wrapping around after 3.
+    pub fn from_index(index: u8) -> Self {
+        Self::ALL[index as usize % Self::ALL.len()]
+    }
+
+    /// The direction `steps` quarter turns on from this one.
+    pub fn turn(self, steps: i8) -> Self {
+        Self::from_index((self as i8 + steps).rem_euclid(Self::ALL.len() as i8) as u8)
+    }
+
+    /// Axis the wipe moves along.
+    pub fn direction(self) -> Direction {
+        match self {
+            WipeDirection::TopDown | WipeDirection::BottomUp => Direction::Vertical,
+            WipeDirection::LeftRight | WipeDirection::RightLeft => Direction::Horizontal,
+        }
+    }
+
+    /// Whether the wipe starts from the trailing (bottom or right) edge.
+    pub fn is_trailing(self) -> bool {
+        matches!(self, WipeDirection::BottomUp | WipeDirection::RightLeft)
+    }
+}
+
+/// Speed below which a bouncing digit is considered settled.
+const SETTLE_VELOCITY: f32 = 0.05;
+
+/// A digit falling into place under gravity, bouncing off the bottom of its block.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GravityTransition {
+    /// Normalised height of the digit, from 0.0 (top of the block) to 1.0 (settled).
+    pub y: f32,
+    /// Downward velocity, in block heights per second.
+    pub velocity: f32,
+}
+
+impl GravityTransition {
+    /// Advances the fall by `dt` under gravity `g`, returning the new normalised height.
+    ///
+    /// On reaching the bottom the digit bounces back up, keeping `restitution`
+    /// of its speed each time, until a bounce is too weak to outlast a tick of gravity.
+    pub fn tick(&mut self, dt: Duration, g: f32, restitution: f32) -> f32 {
+        let dt = dt.as_secs_f32();
+        self.velocity += g * dt;
+        self.y += self.velocity * dt;
+        if self.y >= 1.0 {
+            self.y = 1.0;
+            // Otherwise a resting digit picks up speed every tick and keeps bouncing on the spot
+            self.velocity = match restitution * self.velocity.abs() < g * dt {
+                true => 0.0,
+                false => -self.velocity * restitution
+            };
+        }
+        self.y
+    }
+
+    /// Whether the digit has come to rest at the bottom.
+    pub fn is_settled(&self) -> bool {
+        self.y >= 1.0 && self.velocity.abs() < SETTLE_VELOCITY
+    }
+}
+
+/// Time the reel of a slot machine shows each token while spinning at full speed.
+const SLOT_MACHINE_STEP: Duration = Duration::from_millis(50);
+/// Extra time added to the final steps of a slot machine reel as it slows down.
+const SLOT_MACHINE_SLOWDOWN: Duration = Duration::from_millis(200);
+
+/// A digit spinning through the intermediate digits like a slot machine reel.
+#[derive(Debug, Clone, Default)]
+pub struct SlotMachineTransition {
+    /// Token currently showing on the reel.
+    pub token: String,
+    /// Steps left before the reel lands on the new token.
+    remaining: u32,
+    /// Time since the reel last stepped.
+    elapsed: Duration,
+}
+
+impl SlotMachineTransition {
+    /// Starts spinning from the token `from` towards the token `to`.
+    ///
+    /// Digits spin forwards through every digit in between, anything else lands in one step.
+    pub fn new(from: &str, to: &str) -> Self {
+        let remaining = match (from.parse::<u32>(), to.parse::<u32>()) {
+            (Ok(from), Ok(to)) if from < 10 && to < 10 => (to + 10 - from - 1) % 10 + 1,
+            _ => 1
+        };
+        Self { token: from.to_string(), remaining, elapsed: Duration::ZERO }
+    }
+
+    /// Advances the reel by `dt`, returning whether it has landed on `target`.
+    ///
+    /// Each step takes longer the closer the reel is to landing.
+    pub fn tick(&mut self, dt: Duration, target: &str) -> bool {
+        self.elapsed += dt;
+        while self.remaining > 0 {
+            let delay = SLOT_MACHINE_STEP + SLOT_MACHINE_SLOWDOWN / self.remaining;
+            if self.elapsed < delay {
+                break;
+            }
+            self.elapsed -= delay;
+            self.remaining -= 1;
+            self.token = match self.remaining {
+                0 => target.to_string(),
+                _ => match self.token.parse::<u32>() {
+                    Ok(digit) => ((digit + 1) % 10).to_string(),
+                    Err(_) => target.to_string()
+                }
+            };
+        }
+        self.remaining == 0
+    }
+}
+
+/// Text typed out one character at a time, left to right.
+#[derive(Debug, Clone, Default)]
+pub struct TypewriterTransition {
+    /// Text typed so far, padded with spaces to the full width.
+    pub text: String,
+    /// Number of characters typed so far.
+    cursor: usize,
+    /// Time spent typing.
+    elapsed: Duration,
+}
+
+impl TypewriterTransition {
+    /// Advances typing of `target` by `dt`, returning whether it is fully typed.
+    ///
+    /// Characters are spaced evenly so typing the whole of `target` takes `duration`.
+    pub fn tick(&mut self, dt: Duration, duration: Duration, target: &str) -> bool {
+        self.elapsed += dt;
+        let length = target.chars().count();
+        self.cursor = match duration.is_zero() {
+            true => length,
+            false => ((length as f64 * self.elapsed.as_secs_f64() / duration.as_secs_f64()) as usize).min(length)
+        };
+        self.text = target.chars().take(self.cursor).chain(std::iter::repeat(' ')).take(length).collect();
+        self.cursor == length
+    }
+}
+
+/// Most particles a single block leaves behind at once.
+pub const MAX_PARTICLES: usize = 100;
+/// Chance of each drawn cell of the old digit leaving a particle behind.
+const PARTICLE_DENSITY: f64 = 0.5;
+/// Shortest and longest particle lifetimes, in render ticks.
+const PARTICLE_LIFETIME: (u16, u16) = (10, 40);
+
+/// A character left behind where an old digit was, until its lifetime runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Particle {
+    /// Columns from the centre of the figure, which is drawn centred in its block.
+    pub x: i16,
+    /// Lines from the top of the figure.
+    pub y: u16,
+    /// Character drawn for the particle.
+    pub symbol: char,
+    /// Render ticks left before the particle is cleared.
+    pub lifetime: u16,
+}
+
+/// Scatters particles over the drawn cells of the FIGfont `figure`, each a random
+/// character taken from the figure itself.
+///
+/// The oldest particles make way once there are more than [`MAX_PARTICLES`].
+pub fn scatter_particles(figure: &str, rng: &mut impl Rng, particles: &mut VecDeque<Particle>) {
+    let symbols: Vec<char> = figure.chars().filter(|ch| !ch.is_whitespace()).collect();
+    if symbols.is_empty() {
+        return;
+    }
+    for (y, line) in figure.lines().enumerate() {
+        let centre = line.chars().count() as i16 / 2;
+        for (x, ch) in line.chars().enumerate() {
+            if ch.is_whitespace() || !rng.gen_bool(PARTICLE_DENSITY) {
+                continue;
+            }
+            if particles.len() == MAX_PARTICLES {
+                particles.pop_front();
+            }
+            particles.push_back(Particle {
+                x: x as i16 - centre,
+                y: y as u16,
+                symbol: symbols[rng.gen_range(0..symbols.len())],
+                lifetime: rng.gen_range(PARTICLE_LIFETIME.0..=PARTICLE_LIFETIME.1),
+            });
+        }
+    }
+}
+
+/// Ages every particle by one render tick, clearing those whose lifetime has run out.
+///
+/// Returns whether any particles are left.
+pub fn age_particles(particles: &mut VecDeque<Particle>) -> bool {
+    particles.retain_mut(|particle| {
+        particle.lifetime = particle.lifetime.saturating_sub(1);
+        particle.lifetime > 0
+    });
+    !particles.is_empty()
+}
@@ -1,9 +1,13 @@
 use crate::app::{App, AppResult};
 use crate::event::EventHandler;
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
-use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
 use std::io;
-use tui::backend::Backend;
+use std::thread;
+use std::time::Duration;
+use tui::backend::{Backend, TestBackend};
+use tui::buffer::Cell;
 use tui::Terminal;
 
 /// Representation of a terminal user interface.
@@ -16,22 +20,82 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Off-screen terminal the interface is rendered to before being drawn over the
+    /// existing terminal content, in overlay mode.
+    overlay: Option<Terminal<TestBackend>>,
+    /// Line of the terminal the top of the interface is moved to in overlay mode, if fixed.
+    overlay_line: Option<u16>,
+    /// Lines drawn over in the last overlay frame.
+    overlay_lines: Vec<u16>,
+    /// How long to wait before the first initialization.
+    startup_delay: Option<Duration>,
+    /// When the screen is cleared.
+    clear_mode: ClearMode,
+}
+
+/// When the screen is cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClearMode {
+    /// Once on initialization, redrawing only changed cells from then on.
+    #[default]
+    Init,
+    /// Never, redrawing only changed cells over whatever is on screen.
+    Never,
+    /// Before every frame, redrawing every cell.
+    EveryFrame,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self { terminal, events, overlay: None, overlay_line: None, overlay_lines: Vec::new(), startup_delay: None, clear_mode: ClearMode::Init }
+    }
+
+    /// Draws over the existing terminal content instead of switching to the alternate screen.
+    pub fn set_overlay(mut self, is_overlay: bool) -> Self {
+        self.overlay = match is_overlay {
+            true => Some(Terminal::new(TestBackend::new(0, 0)).expect("Failed to create overlay buffer")),
+            false => None
+        };
+        self
+    }
+
+    /// Fixes the top of the interface to the given line of the terminal in overlay mode.
+    pub fn set_overlay_line(mut self, line: Option<u16>) -> Self {
+        self.overlay_line = line;
+        self
+    }
+
+    /// Waits for `delay` before first taking over the terminal.
+    pub fn set_startup_delay(mut self, delay: Duration) -> Self {
+        self.startup_delay = (!delay.is_zero()).then_some(delay);
+        self
+    }
+
+    /// Sets when the screen is cleared.
+    pub fn set_clear_mode(mut self, clear_mode: ClearMode) -> Self {
+        self.clear_mode = clear_mode;
+        self
     }
 
     /// Initializes the terminal interface.
     ///
     /// It enables the raw mode and sets terminal properties.
     pub fn init(&mut self) -> AppResult<()> {
+        // The terminal is untouched until raw mode is enabled, so Ctrl-C during
+        // the delay simply interrupts the process as usual
+        if let Some(delay) = self.startup_delay.take() {
+            thread::sleep(delay);
+        }
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        crossterm::execute!(io::stderr(), EnableMouseCapture)?;
         self.terminal.hide_cursor()?;
-        self.terminal.clear()?;
+        if self.overlay.is_none() {
+            crossterm::execute!(io::stderr(), EnterAlternateScreen)?;
+            if self.clear_mode != ClearMode::Never {
+                self.terminal.clear()?;
+            }
+        }
         Ok(())
     }
 
@@ -40,16 +104,75 @@ impl<B: Backend> Tui<B> {
     /// [`Draw`]: tui::Terminal::draw
     /// [`rendering`]: crate::app::App::render
     pub fn draw(&mut self, app: &mut App) -> AppResult<()> {
+        if self.overlay.is_some() {
+            return self.draw_overlay(app);
+        }
+        if self.clear_mode == ClearMode::EveryFrame {
+            self.terminal.clear()?;
+        }
         self.terminal.draw(|frame| app.render(frame))?;
         Ok(())
     }
 
+    /// Draws the interface over the existing terminal content, clearing and redrawing only
+    /// the lines it uses.
+    fn draw_overlay(&mut self, app: &mut App) -> AppResult<()> {
+        let size = self.terminal.size()?;
+        let overlay = match &mut self.overlay {
+            Some(overlay) if overlay.size()? == size => overlay,
+            overlay => overlay.insert(Terminal::new(TestBackend::new(size.width, size.height))?)
+        };
+        overlay.draw(|frame| app.render(frame))?;
+        let buffer = overlay.backend().buffer();
+        let used: Vec<u16> = (0..size.height)
+            .filter(|&y| (0..size.width).any(|x| *buffer.get(x, y) != Cell::default()))
+            .collect();
+        // Pairs of the line rendered to and the line of the terminal it is drawn on
+        let shift = |y: u16| match (self.overlay_line, used.first()) {
+            (Some(line), Some(&top)) => line.saturating_add(y - top),
+            _ => y
+        };
+        let rows: Vec<(u16, u16)> = used.iter().map(|&y| (y, shift(y))).filter(|&(_, line)| line < size.height).collect();
+        let lines: Vec<u16> = rows.iter().map(|&(_, line)| line).collect();
+
+        let mut stderr = io::stderr();
+        crossterm::queue!(stderr, SavePosition)?;
+        // Lines from the last frame are cleared too, in case the interface has moved
+        for &y in self.overlay_lines.iter().chain(&lines) {
+            crossterm::queue!(stderr, MoveTo(0, y), Clear(ClearType::CurrentLine))?;
+        }
+        let cells = rows.iter().flat_map(|&(y, line)| (0..size.width).map(move |x| (x, line, buffer.get(x, y))));
+        self.terminal.backend_mut().draw(cells)?;
+        self.terminal.backend_mut().flush()?;
+        crossterm::execute!(stderr, RestorePosition)?;
+        self.overlay_lines = lines;
+        Ok(())
+    }
+
+    /// Reinitializes the terminal interface from scratch.
+    ///
+    /// Used on terminals that don't cope well with resizing.
+    pub fn restart(&mut self) -> AppResult<()> {
+        self.exit()?;
+        self.init()
+    }
+
     /// Exits the terminal interface.
     ///
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> AppResult<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        crossterm::execute!(io::stderr(), DisableMouseCapture)?;
+        match self.overlay {
+            Some(_) => {
+                // The terminal can't be read back, so lines drawn over are left blank
+                for &y in &self.overlay_lines {
+                    crossterm::execute!(io::stderr(), MoveTo(0, y), Clear(ClearType::CurrentLine))?;
+                }
+                self.overlay_lines.clear();
+            }
+            None => crossterm::execute!(io::stderr(), LeaveAlternateScreen)?
+        }
         self.terminal.show_cursor()?;
         Ok(())
     }
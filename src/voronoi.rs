@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tui::style::Color;
+
+/// Fastest a seed drifts, in screen widths or heights per second.
+const MAX_SPEED: f64 = 0.05;
+/// Colours of the seeds' cells, repeating when there are more seeds.
+const COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Green,
+    Color::Red,
+    Color::Yellow,
+];
+
+/// A point that the cells closest to it are coloured after.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Seed {
+    /// Horizontal position, from 0.0 (left edge) to 1.0 (right edge).
+    x: f64,
+    /// Vertical position, from 0.0 (top edge) to 1.0 (bottom edge).
+    y: f64,
+    /// Horizontal velocity, in screen widths per second.
+    vx: f64,
+    /// Vertical velocity, in screen heights per second.
+    vy: f64,
+    color: Color,
+}
+
+/// Voronoi diagram of seeds drifting around the screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voronoi {
+    seeds: Vec<Seed>,
+}
+
+impl Voronoi {
+    /// Scatters `seeds` seeds at random over the screen, each drifting in a random direction.
+    pub fn new(seeds: usize) -> Self {
+        let mut rng = StdRng::from_entropy();
+        let seeds = (0..seeds)
+            .map(|i| Seed {
+                x: rng.gen_range(0.0..1.0),
+                y: rng.gen_range(0.0..1.0),
+                vx: rng.gen_range(-MAX_SPEED..MAX_SPEED),
+                vy: rng.gen_range(-MAX_SPEED..MAX_SPEED),
+                color: COLORS[i % COLORS.len()],
+            })
+            .collect();
+        Self { seeds }
+    }
+
+    /// Moves every seed along by `dt`, bouncing off the edges of the screen.
+    pub fn drift(&mut self, dt: Duration) {
+        let seconds = dt.as_secs_f64();
+        for seed in &mut self.seeds {
+            seed.x += seed.vx * seconds;
+            seed.y += seed.vy * seconds;
+            if !(0.0..=1.0).contains(&seed.x) {
+                seed.vx = -seed.vx;
+                seed.x = seed.x.clamp(0.0, 1.0);
+            }
+            if !(0.0..=1.0).contains(&seed.y) {
+                seed.vy = -seed.vy;
+                seed.y = seed.y.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    /// Colour of the seed closest to the cell at (`x`, `y`) of a `width` by `height` screen.
+    pub fn color_at(&self, x: u16, y: u16, width: u16, height: u16) -> Option<Color> {
+        // Terminal cells are about twice as tall as they are wide
+        let distance = |seed: &Seed| {
+            let dx = (x as f64 + 0.5) - seed.x * width as f64;
+            let dy = 2.0 * ((y as f64 + 0.5) - seed.y * height as f64);
+            dx * dx + dy * dy
+        };
+        self.seeds
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .map(|seed| seed.color)
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::process::{Child, Command, Stdio};
+use std::time::Instant;
+
+/// A shell command re-run on every logic tick, keeping a history of the numbers it prints.
+#[derive(Debug)]
+pub struct Watch {
+    /// Shell command being watched.
+    command: String,
+    /// Run of the command currently in progress.
+    child: Option<Child>,
+    /// When watching started, the origin of the history timestamps.
+    started: Instant,
+    /// Last line printed by the command.
+    output: String,
+    /// Seconds since watching started and the number printed at that time, oldest first.
+    history: VecDeque<(f64, f64)>,
+    /// Number of entries kept in the history.
+    capacity: usize,
+}
+
+impl Watch {
+    /// Watches `command`, keeping the last `capacity` numbers it printed.
+    pub fn new(command: &str, capacity: usize) -> Self {
+        Self {
+            command: command.to_string(),
+            child: None,
+            started: Instant::now(),
+            output: String::new(),
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Collects the output of the previous run if it has finished, and starts the next one.
+    ///
+    /// Never blocks on the command, so a slow command is simply sampled less often.
+    pub fn tick(&mut self) -> io::Result<()> {
+        if let Some(child) = &mut self.child {
+            if child.try_wait()?.is_none() {
+                return Ok(());
+            }
+            let mut stdout = String::new();
+            if let Some(pipe) = &mut child.stdout {
+                pipe.read_to_string(&mut stdout)?;
+            }
+            self.child = None;
+            self.output = stdout.lines().last().unwrap_or_default().trim().to_string();
+            if let Some(value) = self.output.split_whitespace().next().and_then(|word| word.parse::<f64>().ok()) {
+                if self.history.len() == self.capacity {
+                    self.history.pop_front();
+                }
+                self.history.push_back((self.started.elapsed().as_secs_f64(), value));
+            }
+        }
+        self.child = Some(shell(&self.command).stdout(Stdio::piped()).stderr(Stdio::null()).spawn()?);
+        Ok(())
+    }
+
+    /// Last line printed by the command.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Forgets the numbers printed so far, freeing the memory they took up.
+    pub fn clear_history(&mut self) {
+        self.history = VecDeque::with_capacity(self.capacity);
+    }
+
+    /// Numbers printed by the command, as seconds since watching started and value pairs.
+    pub fn history(&self) -> Vec<(f64, f64)> {
+        self.history.iter().copied().collect()
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+/// Builds a command running `command` through the platform shell.
+fn shell(command: &str) -> Command {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut shell = Command::new(shell);
+    shell.args([flag, command]);
+    shell
+}
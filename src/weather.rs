@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::app::AppResult;
+
+/// How often the weather is fetched again.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The parts of a `wttr.in` JSON (`format=j1`) response that are shown.
+#[derive(Debug, Deserialize)]
+struct Report {
+    current_condition: Vec<Condition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Condition {
+    #[serde(rename = "temp_C")]
+    temp_c: String,
+    weather_code: String,
+    weather_desc: Vec<Description>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Description {
+    value: String,
+}
+
+/// Current weather in a city, kept up to date by a background thread.
+#[derive(Debug)]
+pub struct Weather {
+    /// Last weather fetched successfully, kept through network outages.
+    current: Arc<Mutex<Option<String>>>,
+}
+
+impl Weather {
+    /// Starts fetching the weather in `city` in the background, refreshing it every ten minutes.
+    pub fn new(city: &str) -> Self {
+        let current = Arc::new(Mutex::new(None));
+        let shared = Arc::downgrade(&current);
+        let url = format!("https://wttr.in/{}?format=j1", city.trim().replace(' ', "+"));
+        thread::spawn(move || loop {
+            let fetched = fetch_weather(&url);
+            // Stop once the weather is gone
+            let Some(current) = shared.upgrade() else { return };
+            // A failed refresh keeps showing the last weather fetched
+            if let Ok(fetched) = fetched {
+                *current.lock().unwrap() = Some(fetched);
+            }
+            drop(current);
+            thread::sleep(REFRESH_INTERVAL);
+        });
+        Self { current }
+    }
+
+    /// The weather to show, once it has been fetched.
+    pub fn text(&self) -> Option<String> {
+        self.current.lock().unwrap().clone()
+    }
+}
+
+/// Downloads the weather from `url`.
+fn fetch_weather(url: &str) -> AppResult<String> {
+    let body = ureq::get(url).call()?.into_string()?;
+    Ok(parse_weather(&body)?)
+}
+
+/// Condition symbol, temperature and description of the current weather in a `wttr.in` JSON
+/// response, such as `☀ 21°C Sunny`.
+pub fn parse_weather(json: &str) -> Result<String, serde_json::Error> {
+    let report: Report = serde_json::from_str(json)?;
+    let Some(condition) = report.current_condition.first() else {
+        return Err(serde::de::Error::custom("no current condition"));
+    };
+    let description = condition.weather_desc.first().map_or("", |description| description.value.trim());
+    let symbol = condition_symbol(condition.weather_code.parse().unwrap_or_default());
+    Ok(format!("{} {}°C {}", symbol, condition.temp_c, description).trim_end().to_string())
+}
+
+/// Symbol for a `wttr.in` weather code.
+fn condition_symbol(code: u32) -> char {
+    match code {
+        113 => '☀',
+        116 => '⛅',
+        119 | 122 => '☁',
+        143 | 248 | 260 => '≡',
+        200 | 386 | 389 | 392 | 395 => '⚡',
+        179 | 182 | 185 | 227 | 230 | 281 | 284 | 311..=338 | 350 | 362..=377 => '❄',
+        176 | 263..=308 | 353..=359 => '☂',
+        _ => '?'
+    }
+}
@@ -0,0 +1,737 @@
+use std::time::{Duration, SystemTime};
+use std::{env, fs, process};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike};
+use clap::Parser;
+use clocktui::app::{AnimatedTime, App, AppMode};
+use clocktui::builder::ConfigError;
+use clocktui::color::{downgrade_color, hsv_to_rgb, parse_color, ColorDepth, ColorMode};
+use clocktui::config::{AnimationPreset, AppConfig, TimeUnit, TimingSpec};
+use clocktui::easing::EasingFn;
+use clocktui::event::{Event, EventHandler, Events, MockEvents, NoopAnimationTrigger, RENDER_TICK_RATE};
+use clocktui::handler::handle_event;
+use clocktui::style::StyleSheet;
+use clocktui::transition::GravityTransition;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use tui::backend::TestBackend;
+use tui::style::Color;
+use tui::Terminal;
+
+/// Counts heap usage, as the binary does for `--memory-limit`.
+#[cfg(feature = "mem-limit")]
+#[global_allocator]
+static ALLOCATOR: clocktui::memory::TrackingAllocator = clocktui::memory::TrackingAllocator;
+
+/// Runs one iteration of the main loop.
+fn run_once(app: &mut App, events: &EventHandler) {
+    let event = events.next().unwrap();
+    handle_event(event, app, events).unwrap();
+}
+
+#[test]
+fn quit_key_exits_within_one_tick() {
+    let mut app = App::new(AppConfig::default()).unwrap();
+    let events = EventHandler::mock();
+    events.send(Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))).unwrap();
+    run_once(&mut app, &events);
+    assert!(!app.running);
+}
+
+#[test]
+fn set_running_exits_after_tick() {
+    let mut app = App::new(AppConfig::default()).unwrap();
+    let tick = Duration::from_millis(200);
+    let events = MockEvents::new([Event::LogicTick(tick), Event::Terminate, Event::LogicTick(tick)]);
+    let mut iterations = 0;
+    while app.running {
+        handle_event(events.next().unwrap(), &mut app, &events).unwrap();
+        iterations += 1;
+    }
+    assert_eq!(iterations, 2);
+    assert!(events.next().is_ok());
+}
+
+#[test]
+fn config_file_changes_take_effect() {
+    let path = env::temp_dir().join(format!("clocktui-config-{}.toml", process::id()));
+    fs::write(&path, "format = \"%H:%M\"\n").unwrap();
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--config", path.to_str().unwrap()])).unwrap();
+    assert_eq!(app.formatted_time().len(), "HH:MM".len());
+    // Each write is dated later than the last, however coarse the file system's timestamps
+    let write = |contents: &str, seconds: u64| {
+        fs::write(&path, contents).unwrap();
+        fs::File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now() + Duration::from_secs(seconds)).unwrap();
+    };
+    let tick = Duration::from_millis(200);
+
+    // The file was only just polled, so changes wait for the next poll
+    write("format = \"%H:%M:%S\"\ncolor = \"green\"\n", 10);
+    app.tick_logic(tick, &NoopAnimationTrigger);
+    assert_eq!(app.formatted_time().len(), "HH:MM".len());
+    app.set_config_poll_interval(Duration::ZERO);
+    app.tick_logic(tick, &NoopAnimationTrigger);
+    assert_eq!(app.formatted_time().len(), "HH:MM:SS".len());
+
+    let font = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/small.flf");
+    write(&format!("format = \"%H:%M:%S\"\nfont = \"{}\"\n", font), 20);
+    app.tick_logic(tick, &NoopAnimationTrigger);
+    assert_eq!(app.font_name(), font);
+    // A font that can't be loaded keeps the current one
+    write("format = \"%H:%M:%S\"\nfont = \"no-such-font\"\n", 30);
+    app.tick_logic(tick, &NoopAnimationTrigger);
+    assert_eq!(app.font_name(), font);
+
+    // An invalid file leaves the last settings in place
+    write("color = \"not a colour\"\n", 40);
+    app.tick_logic(tick, &NoopAnimationTrigger);
+    assert_eq!(app.formatted_time().len(), "HH:MM:SS".len());
+    assert!(app.hot_reload_config(&path).is_err());
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn format_event_switches_format() {
+    let mut app = App::new(AppConfig::default()).unwrap();
+    let events = EventHandler::mock();
+    events.send(Event::Format("%H:%M".to_string())).unwrap();
+    run_once(&mut app, &events);
+    assert_eq!(app.formatted_time().len(), "HH:MM".len());
+}
+
+#[test]
+fn format_flag_sets_format() {
+    let app = App::new(AppConfig::parse_from(["clocktui", "--format", "%H:%M"])).unwrap();
+    assert_eq!(app.formatted_time().len(), "HH:MM".len());
+}
+
+#[test]
+fn invalid_format_falls_back_to_default() {
+    for format in ["%Q", "%H:%Q", ""] {
+        let app = App::new(AppConfig::parse_from(["clocktui", "--format", format])).unwrap();
+        assert_eq!(app.formatted_time().len(), "HH:MM:SS".len(), "format `{}`", format);
+    }
+}
+
+#[test]
+fn timezone_flag_shows_time_in_timezone() {
+    let app = App::new(AppConfig::parse_from(["clocktui", "--timezone", "Asia/Kolkata", "--format", "%z"])).unwrap();
+    assert_eq!(app.formatted_time(), "+0530");
+    assert!(AppConfig::try_parse_from(["clocktui", "--timezone", "Not/A_Zone"]).is_err());
+}
+
+#[test]
+fn countdown_to_past_datetime_is_over() {
+    let app = App::new(AppConfig::parse_from(["clocktui", "--countdown", "2000-01-01T00:00:00"])).unwrap();
+    assert_eq!(app.mode(), AppMode::Countdown);
+    assert_eq!(app.formatted_time(), "00:00:00");
+    assert!(AppConfig::try_parse_from(["clocktui", "--countdown", "2000-01-01"]).is_err());
+}
+
+#[test]
+fn stopwatch_pauses_and_resets() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--stopwatch"])).unwrap();
+    assert_eq!(app.mode(), AppMode::Stopwatch);
+    let events = EventHandler::mock();
+    events.send(Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE))).unwrap();
+    run_once(&mut app, &events);
+    std::thread::sleep(Duration::from_millis(50));
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    let paused = app.formatted_time();
+    std::thread::sleep(Duration::from_millis(50));
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    assert_eq!(app.formatted_time(), paused);
+
+    events.send(Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE))).unwrap();
+    run_once(&mut app, &events);
+    assert_eq!(app.formatted_time(), "00:00:00.00");
+}
+
+#[cfg(feature = "network")]
+#[test]
+fn news_ticker_parses_item_titles() {
+    let feed = r#"<rss><channel><title>Feed</title>
+        <item><title>First  headline</title></item>
+        <item><title><![CDATA[Second headline]]></title></item>
+    </channel></rss>"#;
+    let headlines = clocktui::news::parse_headlines(feed).unwrap();
+    assert_eq!(headlines, ["First headline", "Second headline"]);
+}
+
+#[cfg(feature = "network")]
+#[test]
+fn weather_parses_current_condition() {
+    let json = r#"{"current_condition": [{"temp_C": "21", "temp_F": "70", "weatherCode": "113",
+        "weatherDesc": [{"value": "Sunny"}]}], "weather": []}"#;
+    assert_eq!(clocktui::weather::parse_weather(json).unwrap(), "☀ 21°C Sunny");
+    assert!(clocktui::weather::parse_weather(r#"{"current_condition": []}"#).is_err());
+}
+
+#[test]
+fn timing_flag_parses_per_unit_timings() {
+    let config = AppConfig::parse_from(["clocktui", "--timing", "hours=300,Seconds=100"]);
+    assert_eq!(config.timing, [
+        TimingSpec { unit: TimeUnit::Hours, timing: 300 },
+        TimingSpec { unit: TimeUnit::Seconds, timing: 100 },
+    ]);
+    assert!(config.timing[0].matches(36000));
+    assert!(!config.timing[1].matches(60));
+    assert!(AppConfig::try_parse_from(["clocktui", "--timing", "weeks=100"]).is_err());
+}
+
+#[test]
+fn easing_curves_run_from_zero_to_one() {
+    for easing in [EasingFn::Linear, EasingFn::EaseIn, EasingFn::EaseOut, EasingFn::EaseInOut, EasingFn::Cubic] {
+        assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+        assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+    }
+    assert!(EasingFn::EaseIn.apply(0.25) < 0.25);
+    assert!(EasingFn::EaseOut.apply(0.25) > 0.25);
+    assert_eq!(EasingFn::EaseInOut.apply(0.5), 0.5);
+}
+
+#[test]
+fn moon_phase_matches_known_new_and_full_moons() {
+    let phase_at = |datetime: &str| {
+        let datetime = DateTime::parse_from_rfc3339(datetime).unwrap();
+        clocktui::moon::moon_phase(clocktui::moon::julian_day(&datetime))
+    };
+    assert_eq!(phase_at("2000-01-06T18:14:00Z"), ('🌑', "New Moon"));
+    assert_eq!(phase_at("2024-01-11T11:57:00Z"), ('🌑', "New Moon"));
+    assert_eq!(phase_at("2024-01-25T17:54:00Z"), ('🌕', "Full Moon"));
+    assert_eq!(phase_at("2025-10-07T03:47:00Z"), ('🌕', "Full Moon"));
+    assert_eq!(phase_at("2024-01-18T03:52:00Z"), ('🌓', "First Quarter"));
+}
+
+#[test]
+fn sunrise_and_sunset_match_known_times() {
+    let minutes = |time: NaiveTime| time.num_seconds_from_midnight() as i64 / 60;
+    let midsummer = NaiveDate::from_ymd(2024, 6, 21);
+    // London, with sunrise at 03:43 and sunset at 20:21 UTC
+    let (lat, lon) = (51.5074, -0.1278);
+    assert!((minutes(clocktui::sun::sunrise(lat, lon, midsummer).unwrap()) - (3 * 60 + 43)).abs() <= 2);
+    assert!((minutes(clocktui::sun::sunset(lat, lon, midsummer).unwrap()) - (20 * 60 + 21)).abs() <= 2);
+    // The sun doesn't set in Tromsø at midsummer
+    assert_eq!(clocktui::sun::sunrise(69.65, 18.96, midsummer), None);
+}
+
+#[test]
+fn missing_font_falls_back_to_standard() {
+    assert!(clocktui::font::Font::load("no-such-font").is_err());
+    assert!(clocktui::font::Font::load("standard").is_ok());
+    let app = App::new(AppConfig::parse_from(["clocktui", "--font", "no-such-font"]));
+    assert!(app.is_ok());
+}
+
+#[cfg(feature = "network")]
+#[test]
+fn ntp_round_trip_times_server_reply() {
+    let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let mut packet = [0; 48];
+        let (_, client) = server.recv_from(&mut packet).unwrap();
+        // Version 3, server mode
+        packet[0] = 0x1c;
+        server.send_to(&packet, client).unwrap();
+    });
+    let rtt = clocktui::ntp::round_trip(addr).unwrap();
+    assert!(rtt < Duration::from_secs(1));
+}
+
+#[test]
+fn parse_color_accepts_hex_and_palette_colors() {
+    assert_eq!(parse_color("#000000"), Ok(Color::Rgb(0, 0, 0)));
+    assert_eq!(parse_color("#FFFFFF"), Ok(Color::Rgb(255, 255, 255)));
+    assert_eq!(parse_color("#FF0000"), Ok(Color::Rgb(255, 0, 0)));
+    assert_eq!(parse_color("#ff8800"), Ok(Color::Rgb(255, 136, 0)));
+    assert_eq!(parse_color("light-blue"), Ok(Color::LightBlue));
+    assert_eq!(parse_color("ansi(0)"), Ok(Color::Indexed(0)));
+    assert_eq!(parse_color("ansi(255)"), Ok(Color::Indexed(255)));
+    for invalid in ["invalid", "#GG0000", "#FFF", "#FF00001", "#", "ansi(256)", "ansi(-1)", "ansi()", "ansi(7"] {
+        assert!(parse_color(invalid).is_err(), "`{}`", invalid);
+    }
+}
+
+#[test]
+fn downgrade_color_picks_nearest_at_depth() {
+    assert_eq!(downgrade_color(Color::Rgb(255, 136, 0), ColorDepth::TrueColor), Color::Rgb(255, 136, 0));
+    assert_eq!(downgrade_color(Color::Rgb(255, 135, 0), ColorDepth::Ansi256), Color::Indexed(208));
+    assert_eq!(downgrade_color(Color::Rgb(128, 128, 128), ColorDepth::Ansi256), Color::Indexed(244));
+    assert_eq!(downgrade_color(Color::LightRed, ColorDepth::Ansi256), Color::LightRed);
+    assert_eq!(downgrade_color(Color::Rgb(250, 10, 10), ColorDepth::Ansi8), Color::Red);
+    assert_eq!(downgrade_color(Color::Indexed(21), ColorDepth::Ansi8), Color::Blue);
+    assert_eq!(downgrade_color(Color::LightCyan, ColorDepth::Ansi8), Color::Cyan);
+    assert_eq!(downgrade_color(Color::Reset, ColorDepth::Ansi8), Color::Reset);
+}
+
+#[test]
+fn rainbow_spreads_hues_across_columns() {
+    assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+    assert_eq!(hsv_to_rgb(1.0 / 3.0, 1.0, 1.0), (0, 255, 0));
+    assert_eq!(hsv_to_rgb(2.0 / 3.0, 1.0, 1.0), (0, 0, 255));
+    let rainbow = ColorMode::Rainbow { speed_hz: 0.5 };
+    assert_eq!(rainbow.color(0, 3, 0.0), Some(Color::Rgb(255, 0, 0)));
+    assert_eq!(rainbow.color(1, 3, 0.0), Some(Color::Rgb(0, 255, 0)));
+    // Two thirds of a second at half a cycle a second moves each hue along a column
+    assert_eq!(rainbow.color(0, 3, 2.0 / 3.0), rainbow.color(1, 3, 0.0));
+    assert_eq!(ColorMode::Plain.color(0, 3, 0.0), None);
+}
+
+#[test]
+fn smart_width_drops_units_that_dont_fit() {
+    let formatted_at_width = |width| {
+        let mut app = App::new(AppConfig::parse_from(["clocktui", "--smart-width", "--format", "%H:%M:%S"])).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(width, 20)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        app.formatted_time()
+    };
+    assert_eq!(formatted_at_width(200).len(), "HH:MM:SS".len());
+    assert_eq!(formatted_at_width(80).len(), "HH:MM".len());
+    assert_eq!(formatted_at_width(40).len(), "HH".len());
+    // Too narrow for even the hours, so shown as plain text
+    assert_eq!(formatted_at_width(10).len(), "HH:MM:SS".len());
+}
+
+#[test]
+fn smart_width_narrows_the_default_format() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--smart-width"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    assert_eq!(app.format_string(), "%H:%M");
+    assert_eq!(app.formatted_time().len(), "HH:MM".len());
+}
+
+#[test]
+fn blink_separator_blanks_separators_every_half_second() {
+    // Letters, unlike some digits, have nothing looking like the `(_)` of a colon
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--blink-separator", "--format", "%P:%P"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    let mut shows_separator = |app: &mut App| {
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content.iter().map(|cell| cell.symbol.as_str()).collect::<String>().contains("(_)")
+    };
+    assert!(shows_separator(&mut app));
+    app.tick_render(Duration::from_millis(500), &NoopAnimationTrigger);
+    assert!(!shows_separator(&mut app));
+    app.tick_render(Duration::from_millis(500), &NoopAnimationTrigger);
+    assert!(shows_separator(&mut app));
+}
+
+#[test]
+fn default_frame_rate_drops_with_more_blocks() {
+    let short = App::new(AppConfig::parse_from(["clocktui", "--format", "%H"])).unwrap();
+    let long = App::new(AppConfig::parse_from(["clocktui", "--format", "%Y-%m-%d %H:%M:%S"])).unwrap();
+    assert!(short.format_tokens_count() < long.format_tokens_count());
+    assert!(short.frame_rate_limit() > long.frame_rate_limit());
+    let limited = App::new(AppConfig::parse_from(["clocktui", "--frame-rate-limit", "5"])).unwrap();
+    assert_eq!(limited.frame_rate_limit(), 5);
+}
+
+#[test]
+fn date_shows_above_the_time() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--date", "--date-format", "dated %Y", "--format", "%P"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..20).map(|y| (0..60).map(|x| buffer.get(x, y).symbol.as_str()).collect()).collect();
+    let date_row = rows.iter().position(|row| row.contains("dated 2")).unwrap();
+    let face_row = rows.iter().position(|row| row.contains('╭')).unwrap();
+    assert!(date_row < face_row);
+}
+
+#[cfg(feature = "mem-limit")]
+#[test]
+fn tracking_allocator_counts_heap_usage() {
+    // Other tests allocate at the same time, so only a large allocation stands out
+    let before = clocktui::memory::allocated();
+    let buffer = vec![0u8; 64 * 1024 * 1024];
+    assert!(clocktui::memory::allocated() >= before + buffer.len() / 2);
+    drop(buffer);
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--sparkline-seconds", "--barchart-seconds", "5", "--particle-trail"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    assert!(app.trimmable_len() > 0);
+    app.trim_memory();
+    assert_eq!(app.trimmable_len(), 0);
+    // Going over the limit is noted on screen rather than written over it
+    let buffer = vec![0u8; 2 * 1024 * 1024];
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--memory-limit", "1", "--sparkline-seconds"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    assert_eq!(app.trimmable_len(), 0);
+    drop(buffer);
+    let mut terminal = Terminal::new(TestBackend::new(200, 40)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let bottom: String = (0..200).map(|x| buffer.get(x, 39).symbol.as_str()).collect();
+    assert!(bottom.contains("memory limit"));
+}
+
+#[test]
+fn milliseconds_show_in_small_font_below_the_time() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--milliseconds", "--format", "%P"])).unwrap();
+    app.tick_render(Duration::from_millis(20), &NoopAnimationTrigger);
+    let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..20).map(|y| (0..60).map(|x| buffer.get(x, y).symbol.as_str()).collect()).collect();
+    let face_bottom = rows.iter().rposition(|row| row.contains('╯')).unwrap();
+    // Every seven-segment digit has a segment at the bottom
+    assert!(rows[face_bottom + 3].contains('|'));
+    // Drawn as plain text, the milliseconds have no transition to wait for
+    let milliseconds = app.milliseconds().unwrap();
+    assert_eq!(milliseconds.displayed(), milliseconds.formatted());
+}
+
+#[test]
+fn set_max_fps_sets_the_frame_rate_limit() {
+    assert_eq!(EventHandler::mock().set_max_fps(30).max_fps(), 30);
+}
+
+#[test]
+fn resize_redraws_and_tiny_terminals_dont_panic() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--circle-clock"])).unwrap();
+    let events = EventHandler::mock();
+    events.send(Event::Resize(0, 0)).unwrap();
+    run_once(&mut app, &events);
+    assert!(matches!(events.next().unwrap(), Event::RenderTick(_)));
+    for (width, height) in [(0, 0), (1, 1), (10, 0), (2, 30)] {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+    }
+}
+
+#[test]
+fn builder_sets_fields_and_checks_constraints() {
+    let config = AppConfig::builder().format(Some("%H:%M".to_string())).stopwatch(true).build().unwrap();
+    assert_eq!(config.format.as_deref(), Some("%H:%M"));
+    assert!(config.stopwatch);
+    assert!(App::new(config).is_ok());
+    let conflicting = AppConfig::builder().stopwatch(true).tabs(Some("tabs.toml".into())).build();
+    assert_eq!(conflicting.unwrap_err(), ConfigError::Conflict("stopwatch", "tabs"));
+    let missing = AppConfig::builder().digital_center(true).build();
+    assert_eq!(missing.unwrap_err(), ConfigError::Requires("digital-center", "circle-clock"));
+    assert!(AppConfig::builder().direction(Some(4)).build().is_err());
+}
+
+#[test]
+fn crate_root_reexports_key_types() {
+    let time = clocktui::AnimatedTime::new().set_format("%H:%M");
+    assert_eq!(time.formatted().chars().count(), 5);
+    let blocks: Vec<&clocktui::TokenBlock> = time.format_tokens.iter().flat_map(|token: &clocktui::Token| &token.blocks).collect();
+    assert!(!blocks.is_empty());
+    let app = clocktui::App::new(clocktui::AppConfig::default()).unwrap();
+    assert!(app.running);
+    let events = clocktui::EventHandler::mock();
+    events.send(clocktui::Event::Terminate).unwrap();
+}
+
+#[test]
+fn too_small_terminal_asks_for_resize() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--format", "%H:%M"])).unwrap();
+    assert!(app.check_size(80, 20));
+    assert!(!app.check_size(20, 20));
+    assert!(!app.check_size(80, 3));
+    let mut terminal = Terminal::new(TestBackend::new(40, 5)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol.as_str()).collect();
+    assert!(text.contains("Terminal too small"));
+    // Rows drawn below the face need room too
+    let min_height = |args: &[&str]| {
+        let app = App::new(AppConfig::parse_from([&["clocktui", "--format", "%H:%M"], args].concat())).unwrap();
+        (0..100).find(|&height| app.check_size(200, height)).unwrap()
+    };
+    assert!(min_height(&["--progress-bar", "--sparkline-seconds", "--moon-phase"]) > min_height(&[]) + 2);
+}
+
+#[test]
+fn block_at_screen_pos_hit_tests_drawn_blocks() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--format", "%H:%M"])).unwrap();
+    assert_eq!(app.animated_time().block_at_screen_pos(40, 10), None);
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let top = (0..20).find(|&y| (0..80).any(|x| buffer.get(x, y).symbol == "╭")).unwrap();
+    let left = (0..80).find(|&x| buffer.get(x, top).symbol == "╭").unwrap();
+    assert_eq!(app.animated_time().block_at_screen_pos(left, top), Some(0));
+    assert_eq!(app.animated_time().block_at_screen_pos(79, 0), None);
+}
+
+#[test]
+fn align_places_face_against_edges() {
+    let face_corner = |args: &[&str]| {
+        let mut app = App::new(AppConfig::parse_from([&["clocktui", "--format", "%H"], args].concat())).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..20).flat_map(|y| (0..80).map(move |x| (x, y))).find(|&(x, y)| buffer.get(x, y).symbol == "╭").unwrap()
+    };
+    assert_eq!(face_corner(&["--align", "left", "--valign", "top"]), (0, 0));
+    let (x, y) = face_corner(&["--align", "right", "--valign", "bottom"]);
+    let (center_x, center_y) = face_corner(&[]);
+    assert!(x > center_x && y > center_y);
+}
+
+#[test]
+fn tooltip_shows_block_under_mouse() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--tooltip-on-hover", "--format", "%H"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    let mut hover = |app: &mut App, column, row| {
+        let events = EventHandler::mock();
+        events.send(Event::Mouse(MouseEvent { kind: MouseEventKind::Moved, column, row, modifiers: KeyModifiers::NONE })).unwrap();
+        run_once(app, &events);
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        terminal.backend().buffer().content.iter().map(|cell| cell.symbol.as_str()).collect::<String>()
+    };
+    // Lays the blocks out before hovering over them
+    hover(&mut app, 0, 0);
+    assert!(hover(&mut app, 40, 10).contains("token     %H"));
+    assert!(!hover(&mut app, 0, 0).contains("token"));
+}
+
+#[test]
+fn compact_drops_boxes_and_narrows_the_face() {
+    let render = |args: &[&str]| {
+        let mut app = App::new(AppConfig::parse_from([&["clocktui", "--format", "%H:%M:%S"], args].concat())).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        terminal.backend().buffer().content.iter().map(|cell| cell.symbol.as_str()).collect::<String>()
+    };
+    assert!(render(&[]).contains("too small"));
+    let compact = render(&["--compact"]);
+    assert!(!compact.contains("too small"));
+    assert!(!compact.contains('╭'));
+}
+
+#[test]
+fn border_styles_keep_the_face_width() {
+    let min_width = |style: &str| {
+        let app = App::new(AppConfig::parse_from(["clocktui", "--border", style, "--format", "%H:%M"])).unwrap();
+        (0..200).find(|&width| app.check_size(width, 40)).unwrap()
+    };
+    let widths: Vec<u16> = ["rounded", "single", "double", "thick", "none"].iter().map(|style| min_width(style)).collect();
+    assert!(widths.iter().all(|&width| width == widths[0]));
+    for (style, corner) in [("single", "┌"), ("double", "╔"), ("thick", "┏")] {
+        let mut app = App::new(AppConfig::parse_from(["clocktui", "--border", style, "--format", "%H"])).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(terminal.backend().buffer().content.iter().any(|cell| cell.symbol == corner));
+    }
+}
+
+#[test]
+fn mock_events_drive_the_main_loop_without_threads() {
+    let mut app = App::new(AppConfig::default()).unwrap();
+    let events = MockEvents::new([
+        Event::Resize(80, 20),
+        Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)),
+    ]);
+    while let Ok(event) = events.next() {
+        handle_event(event, &mut app, &events).unwrap();
+    }
+    assert!(!app.running);
+    assert!(events.next().is_err());
+}
+
+#[test]
+fn style_file_colours_digits_and_borders() {
+    assert!(StyleSheet::parse(".digit { colour: red; }").is_err());
+    assert!(StyleSheet::parse(".hour { color: red; }").is_err());
+    assert!(StyleSheet::parse(".digit { color: red;").is_err());
+    assert!(StyleSheet::parse(".constant { border: double; }").is_err());
+    assert_eq!(StyleSheet::parse(".transition { border: none; }").unwrap().transition.border_type(), Some(None));
+    let path = env::temp_dir().join(format!("clocktui-style-{}.css", process::id()));
+    fs::write(&path, "/* digits */\n.digit { color: cyan; border: double; }\n.constant { color: white; }\n.transition { color: yellow; }\n").unwrap();
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--style-file", path.to_str().unwrap(), "--format", "%H"])).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(app.style_sheet().digit.color, Some(Color::Cyan));
+    assert_eq!(app.style_sheet().transition.color, Some(Color::Yellow));
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let content = &terminal.backend().buffer().content;
+    assert!(content.iter().any(|cell| cell.symbol == "╔" && cell.fg == Color::Cyan));
+}
+
+#[test]
+fn vertical_stacks_blocks_top_to_bottom() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--vertical", "--format", "%H%M"])).unwrap();
+    assert!(!app.check_size(80, 20));
+    assert!(app.check_size(20, 40));
+    let mut terminal = Terminal::new(TestBackend::new(20, 40)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let column: Vec<usize> = (0..40).filter_map(|y| app.animated_time().block_at_screen_pos(10, y)).collect();
+    assert_eq!(column.first(), Some(&0));
+    assert_eq!(column.last(), Some(&3));
+    assert!(column.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(AppConfig::try_parse_from(["clocktui", "--vertical", "--two-line"]).is_err());
+}
+
+#[test]
+fn tick_and_render_rates_are_validated() {
+    let config = AppConfig::parse_from(["clocktui", "--tick-rate", "1000", "--render-rate", "5"]);
+    assert_eq!((config.tick_rate, config.render_rate), (Some(1000), Some(5)));
+    assert_eq!(App::new(AppConfig::default()).unwrap().render_rate(), RENDER_TICK_RATE);
+    // A faster render rate isn't held back by the frame rate worked out for the format
+    let app = App::new(AppConfig::parse_from(["clocktui", "--render-rate", "10"])).unwrap();
+    assert_eq!(app.capped_render_tick_rate(app.render_rate()), 10);
+    let app = App::new(AppConfig::parse_from(["clocktui", "--render-rate", "10", "--frame-rate-limit", "20"])).unwrap();
+    assert_eq!(app.capped_render_tick_rate(app.render_rate()), 50);
+    for rate in ["0", "1001"] {
+        assert!(AppConfig::try_parse_from(["clocktui", "--render-rate", rate]).is_err());
+    }
+    assert!(AppConfig::try_parse_from(["clocktui", "--tick-rate", "0"]).is_err());
+    assert!(matches!(AppConfig::builder().render_rate(Some(1001)).build(), Err(ConfigError::Invalid("render-rate", _))));
+}
+
+#[test]
+fn animation_presets_fill_in_unset_flags() {
+    let config = AppConfig::parse_with_preset(["clocktui", "--animation-preset", "gentle"]);
+    assert_eq!((config.transition_timing, config.easing), (500, EasingFn::EaseInOut));
+    // Flags passed with their default values still win over the preset
+    let config = AppConfig::parse_with_preset(["clocktui", "--animation-preset", "gentle", "--transition-timing", "250", "--easing", "linear"]);
+    assert_eq!((config.transition_timing, config.easing), (250, EasingFn::Linear));
+    let config = AppConfig::parse_with_preset(["clocktui", "--animation-preset", "dramatic", "--transition-timing", "300", "--random-direction"]);
+    assert_eq!(config.transition_timing, 300);
+    assert!(config.spring && config.random_direction && !config.dual_direction);
+    let config = AppConfig::parse_with_preset(["clocktui", "--animation-preset", "typewriter"]);
+    assert!(config.typewriter && config.stagger > 0);
+    let config = AppConfig::builder().animation_preset(Some(AnimationPreset::Snappy)).transition_timing(250).build().unwrap();
+    assert_eq!(config.transition_timing, 250);
+    assert!(config.random_direction);
+}
+
+#[test]
+fn gravity_digits_settle_at_every_tick_rate() {
+    for tick in [16, 20, 33] {
+        let mut gravity = GravityTransition::default();
+        let settled = (0..1000).any(|_| {
+            gravity.tick(Duration::from_millis(tick), 16.0, 0.4);
+            gravity.is_settled()
+        });
+        assert!(settled, "never settled at {} ms ticks", tick);
+    }
+}
+
+#[test]
+fn timing_applies_to_digits_of_the_default_format() {
+    let app = App::new(AppConfig::parse_from(["clocktui", "--timing", "hours=300,seconds=100"])).unwrap();
+    let timings: Vec<u128> = app.animated_time().format_tokens.iter()
+        .flat_map(|token| &token.blocks)
+        .filter(|block| !block.is_constant)
+        .map(|block| block.transition_timing)
+        .collect();
+    assert_eq!(timings, [300, 300, 250, 250, 100, 100]);
+}
+
+#[test]
+fn stagger_counts_only_blocks_that_changed() {
+    let mut time = AnimatedTime::new().set_stagger(200).set_format("%Y");
+    time.tick_logic();
+    let delays = |time: &AnimatedTime| time.format_tokens[0].blocks.iter().map(|block| block.stagger_delay).collect::<Vec<_>>();
+    assert_eq!(delays(&time), [0, 200, 400, 600]);
+    time.format_tokens[0].blocks[3].new_token = String::from("x");
+    time.tick_logic();
+    assert_eq!(delays(&time)[3], 0);
+}
+
+#[test]
+fn now_honours_the_timezone() {
+    let app = App::new(AppConfig::parse_from(["clocktui", "--timezone", "Pacific/Kiritimati"])).unwrap();
+    assert_eq!(app.now().offset().local_minus_utc(), 14 * 3600);
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--timezone", "Etc/GMT+12", "--table-mode", "--format", "%z"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let screen: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol.as_str()).collect();
+    assert!(screen.contains("-1200"));
+}
+
+#[test]
+fn hypno_rings_keep_the_gradient_background() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--hypno", "--gradient-bg", "blue", "magenta"])).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    assert_eq!(buffer.get(0, 0).bg, Color::Blue);
+    assert!(buffer.content.iter().filter(|cell| cell.symbol.chars().any(|ch| ('\u{2801}'..='\u{28ff}').contains(&ch))).all(|cell| cell.bg == Color::Blue));
+    assert!(AppConfig::try_parse_from(["clocktui", "--hypno", "101"]).is_err());
+}
+
+#[test]
+fn valign_bottom_keeps_rows_below_the_face_on_screen() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--format", "%H", "--valign", "bottom", "--moon-phase"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let last_row: String = (0..80).map(|x| buffer.get(x, 19).symbol.as_str()).collect();
+    assert!(last_row.contains(" Moon") || last_row.contains("Crescent") || last_row.contains("Quarter") || last_row.contains("Gibbous"));
+}
+
+#[test]
+fn countdowns_over_a_day_show_the_days() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--countdown", "2000:00"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    assert!(app.formatted_time().starts_with("1d 09:19:5"), "{}", app.formatted_time());
+}
+
+#[test]
+fn benchmark_measures_transitioning_frames() {
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--format", "%H:%M"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    let before = app.formatted_time();
+    app.benchmark(1).unwrap();
+    assert!(app.animated_time().is_transitioning());
+    assert_ne!(app.formatted_time(), before);
+}
+
+#[test]
+fn style_file_changes_take_effect() {
+    let path = env::temp_dir().join(format!("clocktui-style-reload-{}.css", process::id()));
+    fs::write(&path, ".digit { color: cyan; }\n").unwrap();
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--style-file", path.to_str().unwrap()])).unwrap();
+    fs::write(&path, ".digit { color: red; }\n").unwrap();
+    // Later than the first write, however coarse the file system's timestamps
+    fs::File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(app.style_sheet().digit.color, Some(Color::Red));
+}
+
+#[test]
+fn tabs_switch_timezone_and_font() {
+    let path = env::temp_dir().join(format!("clocktui-tabs-{}.toml", process::id()));
+    let font = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/small.flf");
+    fs::write(&path, format!("[[preset]]\nname = \"UTC\"\ntimezone = \"UTC\"\n\n[[preset]]\nname = \"Tokyo\"\ntimezone = \"Asia/Tokyo\"\nfont = \"{}\"\n", font)).unwrap();
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--tabs", path.to_str().unwrap()])).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(app.now().offset().local_minus_utc(), 0);
+    assert_eq!(app.font_name(), "standard");
+    app.next_tab();
+    assert_eq!(app.now().offset().local_minus_utc(), 9 * 3600);
+    assert_eq!(app.font_name(), font);
+    app.next_tab();
+    assert_eq!(app.font_name(), "standard");
+}
+
+#[test]
+fn bundled_fonts_load_by_name() {
+    for name in ["small", "slant", "block"] {
+        let font = clocktui::font::Font::load(name).unwrap();
+        assert_eq!(font.name(), name);
+        assert!(font.convert("12:34").is_some_and(|figure| !figure.to_string().trim().is_empty()));
+    }
+}
+
+#[test]
+fn figures_fall_back_to_the_standard_font_then_nothing() {
+    let standard = clocktui::font::Font::standard();
+    let font = clocktui::font::Font::load("small").unwrap();
+    assert_eq!(font.figure("1"), font.convert("1").unwrap().to_string());
+    // Only the standard font has Latin-2 letters, and neither has a snowman
+    assert!(font.convert("Ą").is_none());
+    assert_eq!(font.figure("Ą"), standard.convert("Ą").unwrap().to_string());
+    assert_eq!(font.figure("☃"), "");
+    let mut app = App::new(AppConfig::parse_from(["clocktui", "--font", "small", "--format", "☃%S"])).unwrap();
+    app.tick_logic(Duration::from_millis(200), &NoopAnimationTrigger);
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+}